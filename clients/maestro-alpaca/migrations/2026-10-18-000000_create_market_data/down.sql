@@ -0,0 +1,5 @@
+DROP TABLE alpaca_orders;
+DROP TABLE alpaca_trades;
+DROP TABLE alpaca_quotes;
+DROP TABLE alpaca_bars;
+DROP TYPE alpaca_timeframe;
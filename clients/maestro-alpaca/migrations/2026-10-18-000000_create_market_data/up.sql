@@ -0,0 +1,69 @@
+CREATE TYPE alpaca_timeframe AS ENUM (
+	'minute',
+	'minute5',
+	'minute15',
+	'minute30',
+	'hour',
+	'hour2',
+	'hour6',
+	'hour12',
+	'day',
+	'month',
+	'month2',
+	'month3',
+	'month4',
+	'month6',
+	'month12'
+);
+
+CREATE TABLE alpaca_bars (
+	symbol TEXT NOT NULL,
+	timeframe alpaca_timeframe NOT NULL,
+	time TIMESTAMPTZ NOT NULL,
+	open REAL NOT NULL,
+	close REAL NOT NULL,
+	high REAL NOT NULL,
+	low REAL NOT NULL,
+	volume INTEGER NOT NULL,
+	trade_count INTEGER NOT NULL,
+	weighted_average REAL NOT NULL,
+	PRIMARY KEY (symbol, timeframe, time)
+);
+
+CREATE TABLE alpaca_quotes (
+	id BIGSERIAL PRIMARY KEY,
+	symbol TEXT NOT NULL,
+	time TIMESTAMPTZ NOT NULL,
+	ask_exchange TEXT NOT NULL,
+	ask_price REAL NOT NULL,
+	ask_size BIGINT NOT NULL,
+	bid_exchange TEXT NOT NULL,
+	bid_price REAL NOT NULL,
+	bid_size BIGINT NOT NULL,
+	conditions TEXT[] NOT NULL,
+	tape TEXT NOT NULL,
+	-- Quotes have no ID, backfilling a stored range again must not duplicate them.
+	UNIQUE (symbol, time, ask_exchange, ask_price, ask_size, bid_exchange, bid_price, bid_size)
+);
+
+CREATE TABLE alpaca_trades (
+	id BIGSERIAL PRIMARY KEY,
+	symbol TEXT NOT NULL,
+	time TIMESTAMPTZ NOT NULL,
+	price NUMERIC NOT NULL,
+	size BIGINT NOT NULL,
+	exchange TEXT NOT NULL,
+	trade_id BIGINT NOT NULL,
+	UNIQUE (symbol, time, exchange, trade_id)
+);
+
+CREATE TABLE alpaca_orders (
+	id UUID PRIMARY KEY,
+	client_order_id TEXT NOT NULL,
+	symbol TEXT NOT NULL,
+	created_at TIMESTAMPTZ NOT NULL,
+	updated_at TIMESTAMPTZ,
+	data JSONB NOT NULL
+);
+
+CREATE INDEX alpaca_orders_symbol_created_at ON alpaca_orders (symbol, created_at);
@@ -0,0 +1,79 @@
+use {
+	serde::{Deserialize, Serialize},
+	strum_macros::{Display, EnumIter, EnumString},
+};
+
+/// The trading environment. Paper trading uses simulated money and is the
+/// default.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter, Display, EnumString)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum AlpacaEnv {
+	#[default]
+	Paper,
+	Live,
+}
+
+/// Base URLs of the trading and market data APIs, and of the market data
+/// stream, without trailing slash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, bon::Builder)]
+pub struct AlpacaUrls {
+	#[builder(into)]
+	pub api: String,
+	#[builder(into, default = AlpacaUrls::DATA)]
+	pub data: String,
+	#[builder(into, default = AlpacaUrls::STREAM)]
+	pub stream: String,
+}
+
+impl AlpacaUrls {
+	pub const API_URL_VAR: &'static str = "APCA_API_BASE_URL";
+	pub const DATA: &'static str = "https://data.alpaca.markets";
+	pub const DATA_URL_VAR: &'static str = "APCA_API_DATA_URL";
+	pub const LIVE: &'static str = "https://api.alpaca.markets";
+	pub const PAPER: &'static str = "https://paper-api.alpaca.markets";
+	pub const STREAM: &'static str = "wss://stream.data.alpaca.markets";
+	pub const STREAM_URL_VAR: &'static str = "APCA_API_STREAM_URL";
+
+	pub fn paper() -> Self {
+		Self::from(AlpacaEnv::Paper)
+	}
+
+	pub fn live() -> Self {
+		Self::from(AlpacaEnv::Live)
+	}
+
+	/// Read [`Self::API_URL_VAR`], [`Self::DATA_URL_VAR`] and
+	/// [`Self::STREAM_URL_VAR`], falling back to the paper, data and stream
+	/// URLs.
+	pub fn from_env() -> Self {
+		let api = std::env::var(Self::API_URL_VAR).unwrap_or_else(|_| Self::PAPER.to_owned());
+		let data = std::env::var(Self::DATA_URL_VAR).unwrap_or_else(|_| Self::DATA.to_owned());
+		let stream = std::env::var(Self::STREAM_URL_VAR).unwrap_or_else(|_| Self::STREAM.to_owned());
+		Self::builder().api(api.trim_end_matches('/')).data(data.trim_end_matches('/')).stream(stream.trim_end_matches('/')).build()
+	}
+
+	pub fn env(&self) -> Option<AlpacaEnv> {
+		match self.api.as_str() {
+			Self::PAPER => Some(AlpacaEnv::Paper),
+			Self::LIVE => Some(AlpacaEnv::Live),
+			_ => None,
+		}
+	}
+}
+
+impl Default for AlpacaUrls {
+	fn default() -> Self {
+		Self::paper()
+	}
+}
+
+impl From<AlpacaEnv> for AlpacaUrls {
+	fn from(env: AlpacaEnv) -> Self {
+		let api = match env {
+			AlpacaEnv::Paper => Self::PAPER,
+			AlpacaEnv::Live => Self::LIVE,
+		};
+		Self::builder().api(api).build()
+	}
+}
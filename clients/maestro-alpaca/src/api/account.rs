@@ -1,18 +1,20 @@
+#[cfg(feature = "server")]
+use {
+	crate::{error::AlpacaResult, get_client::AlpacaClient, routes::EApiRoute},
+	reqwest::Method,
+};
 use {
-	crate::{
-		alpaca_env::AlpacaUrls,
-		routes::{EAlpacaRoute, EApiRoute},
-	},
 	bigdecimal::BigDecimal,
 	chrono::{DateTime, Utc},
-	reqwest::Client,
 	serde::{Deserialize, Serialize},
 	uuid::Uuid,
 };
 
-#[bon::builder]
-pub async fn account_get_request(urls: AlpacaUrls, #[builder(default = Client::new())] client: Client) -> Result<Account, reqwest::Error> {
-	client.get(EAlpacaRoute::Api(EApiRoute::AccountActivities).url_path(urls)).send().await?.json().await
+#[cfg(feature = "server")]
+impl AlpacaClient {
+	pub async fn account(&self) -> AlpacaResult<Account> {
+		self.send(self.api(Method::GET, EApiRoute::Account)).await
+	}
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
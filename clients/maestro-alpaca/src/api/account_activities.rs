@@ -1,19 +1,21 @@
+#[cfg(feature = "server")]
 use {
-	super::enums::{activity_type::ActivityType, direction::Direction, side::SideBSSS},
-	crate::{
-		alpaca_env::AlpacaUrls,
-		routes::{EAlpacaRoute, EApiRoute},
-	},
+	super::enums::direction::Direction,
+	crate::{data::last_quotes::last_quotes_dtos::serialize_enums_to_csv, error::AlpacaResult, get_client::AlpacaClient, routes::EApiRoute},
+	reqwest::Method,
+};
+use {
+	super::enums::{activity_type::ActivityType, side::SideBSSS},
 	bigdecimal::BigDecimal,
 	chrono::{DateTime, Utc},
-	reqwest::Client,
 	serde::{Deserialize, Serialize},
 	uuid::Uuid,
 };
 
+#[cfg(feature = "server")]
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 struct ActivityRequest {
-	#[serde(rename = "activity_types")]
+	#[serde(rename = "activity_types", serialize_with = "serialize_enums_to_csv", skip_serializing_if = "Vec::is_empty")]
 	types: Vec<ActivityType>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	direction: Option<Direction>,
@@ -27,24 +29,24 @@ struct ActivityRequest {
 	page_token: Option<String>,
 }
 
-#[bon::builder]
-pub async fn account_activities_get_request(
-	urls: AlpacaUrls,
-	types: Vec<ActivityType>,
-	direction: Option<Direction>,
-	until: Option<DateTime<Utc>>,
-	after: Option<DateTime<Utc>>,
-	page_size: Option<usize>,
-	page_token: Option<String>,
-	#[builder(default = Client::new())] client: Client,
-) -> Result<Activity, reqwest::Error> {
-	client
-		.get(EAlpacaRoute::Api(EApiRoute::AccountActivities).url_path(urls))
-		.query(&ActivityRequest { types, direction, until, after, page_size, page_token })
-		.send()
-		.await?
-		.json()
-		.await
+#[cfg(feature = "server")]
+#[bon::bon]
+impl AlpacaClient {
+	/// Account activities, most recent first by default. All types when
+	/// `types` is empty.
+	#[builder]
+	pub async fn account_activities(
+		&self,
+		#[builder(default)] types: Vec<ActivityType>,
+		direction: Option<Direction>,
+		until: Option<DateTime<Utc>>,
+		after: Option<DateTime<Utc>>,
+		page_size: Option<usize>,
+		page_token: Option<String>,
+	) -> AlpacaResult<Vec<Activity>> {
+		let query = ActivityRequest { types, direction, until, after, page_size, page_token };
+		self.send(self.api(Method::GET, EApiRoute::AccountActivities).query(&query)).await
+	}
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -80,6 +82,7 @@ pub struct NonTradeActivity {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
 pub enum Activity {
 	Trade(TradeActivity),
 	NonTrade(NonTradeActivity),
@@ -1,19 +1,23 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
 use {
-	crate::{
-		alpaca_env::AlpacaUrls,
-		routes::{EAlpacaRoute, EApiRoute},
-	},
-	reqwest::Client,
-	serde::{Deserialize, Serialize},
+	crate::{error::AlpacaResult, get_client::AlpacaClient, routes::EApiRoute},
+	reqwest::Method,
 };
 
-#[bon::builder]
-pub async fn account_configurations_get_request(urls: AlpacaUrls, #[builder(default = Client::new())] client: Client) -> Result<Configuration, reqwest::Error> {
-	client.get(EAlpacaRoute::Api(EApiRoute::AccountActivities).url_path(urls)).send().await?.json().await
+#[cfg(feature = "server")]
+impl AlpacaClient {
+	pub async fn account_configurations(&self) -> AlpacaResult<Configuration> {
+		self.send(self.api(Method::GET, EApiRoute::AccountConfigurations)).await
+	}
+
+	pub async fn update_account_configurations(&self, configuration: &Configuration) -> AlpacaResult<Configuration> {
+		self.send(self.api(Method::PATCH, EApiRoute::AccountConfigurations).json(configuration)).await
+	}
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-enum TradeConfirmation {
+pub enum TradeConfirmation {
 	#[serde(rename = "all")]
 	Email,
 	#[serde(rename = "none")]
@@ -21,7 +25,7 @@ enum TradeConfirmation {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-struct Configuration {
+pub struct Configuration {
 	#[serde(rename = "trade_confirm_email")]
 	pub trade_confirmation: TradeConfirmation,
 	#[serde(rename = "suspend_trade")]
@@ -1,9 +1,9 @@
+#[cfg(feature = "server")]
+use {
+	crate::{error::AlpacaResult, get_client::AlpacaClient, routes::EApiRoute},
+	reqwest::Method,
+};
 use {
-	crate::{
-		alpaca_env::AlpacaUrls,
-		routes::{EAlpacaRoute, EApiRoute},
-	},
-	reqwest::Client,
 	serde::{Deserialize, Serialize},
 	std::fmt::{Display, Formatter, Result as FmtResult},
 	uuid::{Error as UuidError, Uuid},
@@ -78,6 +78,8 @@ pub enum Exchange {
 	Nysearca,
 	#[serde(rename = "OTC")]
 	Otc,
+	#[serde(rename = "CRYPTO")]
+	Crypto,
 }
 
 impl AsRef<str> for Exchange {
@@ -90,6 +92,7 @@ impl AsRef<str> for Exchange {
 			Exchange::Nyse => "NYSE",
 			Exchange::Nysearca => "NYSEARCA",
 			Exchange::Otc => "OTC",
+			Exchange::Crypto => "CRYPTO",
 		}
 	}
 }
@@ -108,20 +111,27 @@ pub struct Asset {
 	pub fractionable: bool,
 }
 
+#[cfg(feature = "server")]
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 struct AssetsGetRequest {
+	#[serde(skip_serializing_if = "Option::is_none")]
 	status: Option<Status>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	asset_class: Option<Class>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	exchange: Option<Exchange>,
 }
 
-#[bon::builder]
-pub async fn assets_get_request(
-	urls: AlpacaUrls,
-	status: Option<Status>,
-	asset_class: Option<Class>,
-	exchange: Option<Exchange>,
-	#[builder(default = Client::new())] client: Client,
-) -> Result<Asset, reqwest::Error> {
-	client.get(EAlpacaRoute::Api(EApiRoute::Assets).url_path(urls)).query(&AssetsGetRequest { status, asset_class, exchange }).send().await?.json().await
+#[cfg(feature = "server")]
+#[bon::bon]
+impl AlpacaClient {
+	#[builder]
+	pub async fn assets(&self, status: Option<Status>, asset_class: Option<Class>, exchange: Option<Exchange>) -> AlpacaResult<Vec<Asset>> {
+		self.send(self.api(Method::GET, EApiRoute::Assets).query(&AssetsGetRequest { status, asset_class, exchange })).await
+	}
+
+	/// An asset by symbol or asset ID.
+	pub async fn asset(&self, symbol: &str) -> AlpacaResult<Asset> {
+		self.send(self.api(Method::GET, EApiRoute::Asset(symbol.to_owned()))).await
+	}
 }
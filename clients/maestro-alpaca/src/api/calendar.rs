@@ -1,10 +1,10 @@
+#[cfg(feature = "server")]
+use {
+	crate::{error::AlpacaResult, get_client::AlpacaClient, routes::EApiRoute},
+	reqwest::Method,
+};
 use {
-	crate::{
-		alpaca_env::AlpacaUrls,
-		routes::{EAlpacaRoute, EApiRoute},
-	},
 	chrono::{NaiveDate, NaiveTime},
-	reqwest::Client,
 	serde::{Deserialize, Serialize},
 };
 
@@ -16,14 +16,14 @@ pub struct CalendarGetRequest {
 	pub end: Option<NaiveDate>,
 }
 
-#[bon::builder]
-pub async fn assets_get_request(
-	urls: AlpacaUrls,
-	start: Option<NaiveDate>,
-	end: Option<NaiveDate>,
-	#[builder(default = Client::new())] client: Client,
-) -> Result<OpenCloseDTO, reqwest::Error> {
-	client.get(EAlpacaRoute::Api(EApiRoute::Calendar).url_path(urls)).query(&CalendarGetRequest { start, end }).send().await?.json().await
+#[cfg(feature = "server")]
+#[bon::bon]
+impl AlpacaClient {
+	/// Market days between `start` and `end`, with their opening hours.
+	#[builder]
+	pub async fn calendar(&self, start: Option<NaiveDate>, end: Option<NaiveDate>) -> AlpacaResult<Vec<OpenCloseDTO>> {
+		self.send(self.api(Method::GET, EApiRoute::Calendar).query(&CalendarGetRequest { start, end })).await
+	}
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -1,16 +1,18 @@
+#[cfg(feature = "server")]
+use {
+	crate::{error::AlpacaResult, get_client::AlpacaClient, routes::EApiRoute},
+	reqwest::Method,
+};
 use {
-	crate::{
-		alpaca_env::AlpacaUrls,
-		routes::{EAlpacaRoute, EApiRoute},
-	},
 	chrono::{DateTime, Utc},
-	reqwest::Client,
 	serde::{Deserialize, Serialize},
 };
 
-#[bon::builder]
-pub async fn clock_get_request(urls: AlpacaUrls, #[builder(default = Client::new())] client: Client) -> Result<ClockDTO, reqwest::Error> {
-	client.get(EAlpacaRoute::Api(EApiRoute::Clock).url_path(urls)).send().await?.json().await
+#[cfg(feature = "server")]
+impl AlpacaClient {
+	pub async fn clock(&self) -> AlpacaResult<ClockDTO> {
+		self.send(self.api(Method::GET, EApiRoute::Clock)).await
+	}
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
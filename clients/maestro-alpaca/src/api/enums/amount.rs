@@ -3,10 +3,17 @@ use {
 	serde::{Deserialize, Serialize},
 };
 
+/// Flattened into orders as either `qty` or `notional`.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
 pub enum Amount {
-	Quantity { quantity: BigDecimal },
-	Notional { notional: BigDecimal },
+	Quantity {
+		#[serde(rename = "qty")]
+		quantity: BigDecimal,
+	},
+	Notional {
+		notional: BigDecimal,
+	},
 }
 
 impl Amount {
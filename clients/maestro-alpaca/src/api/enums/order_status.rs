@@ -34,4 +34,6 @@ pub enum OrderStatus {
 	PendingReplace,
 	#[serde(rename = "calculated")]
 	Calculated,
+	#[serde(rename = "accepted")]
+	Accepted,
 }
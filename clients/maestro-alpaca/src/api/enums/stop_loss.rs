@@ -7,10 +7,12 @@ use {
 #[serde(rename = "stop_loss")]
 struct StopLossSerde {
 	stop_price: BigDecimal,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	limit_price: Option<BigDecimal>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "StopLossSerde", into = "StopLossSerde")]
 pub enum StopLoss {
 	Stop(BigDecimal),
 	StopLimit(BigDecimal, BigDecimal),
@@ -18,11 +20,7 @@ pub enum StopLoss {
 
 impl From<StopLossSerde> for StopLoss {
 	fn from(other: StopLossSerde) -> Self {
-		if let Some(limit_price) = other.limit_price {
-			Self::StopLimit(other.stop_price, limit_price)
-		} else {
-			Self::Stop(other.stop_price)
-		}
+		if let Some(limit_price) = other.limit_price { Self::StopLimit(other.stop_price, limit_price) } else { Self::Stop(other.stop_price) }
 	}
 }
 
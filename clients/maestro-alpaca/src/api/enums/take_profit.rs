@@ -10,6 +10,7 @@ struct TakeProfitSerde {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "TakeProfitSerde", into = "TakeProfitSerde")]
 pub enum TakeProfit {
 	Limit(BigDecimal),
 }
@@ -11,7 +11,7 @@ pub enum StreamType {
 	OrderUpdates,
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AuthenticationStatus {
 	#[serde(rename = "authorized")]
 	Authorized,
@@ -19,9 +19,12 @@ pub enum AuthenticationStatus {
 	Unauthorized,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Authentication {
 	pub status: AuthenticationStatus,
+	/// Why the authentication failed, when the server says.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub message: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -36,11 +39,43 @@ pub enum Authenticate<'d> {
 	},
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// Asks for the streams to receive, replacing the previous ones.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", content = "data")]
+pub enum Listen {
+	#[serde(rename = "listen")]
+	Request { streams: Vec<StreamType> },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OrderUpdate {
 	pub event: OrderStatus,
 	pub order: OrderDTO,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum OrderUpdates {}
+/// Messages of the trading stream, tagged by `stream` with the payload in
+/// `data`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "stream", content = "data")]
+pub enum OrderUpdates {
+	#[serde(rename = "authorization")]
+	Authorization(Authentication),
+	#[serde(rename = "listening")]
+	Listening { streams: Vec<StreamType> },
+	#[serde(rename = "trade_updates")]
+	Update(Box<OrderUpdate>),
+	#[serde(other)]
+	Other,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn failed_authorizations_keep_the_server_message() {
+		let reply = r#"{"stream":"authorization","data":{"action":"authenticate","message":"access key verification failed","status":"unauthorized"}}"#;
+		let expected = Authentication { status: AuthenticationStatus::Unauthorized, message: Some("access key verification failed".to_owned()) };
+		assert_eq!(serde_json::from_str::<OrderUpdates>(reply).unwrap(), OrderUpdates::Authorization(expected));
+	}
+}
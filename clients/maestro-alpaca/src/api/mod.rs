@@ -8,5 +8,7 @@ pub mod enums;
 pub mod order;
 pub mod orders;
 pub mod position;
+#[cfg(feature = "server")]
+pub mod trade_updates;
 pub mod watchlist;
 pub mod watchlists;
@@ -1,11 +1,12 @@
 use {
 	super::{
-		assets::Symbol,
+		assets::Class as AssetClass,
 		enums::{amount::Amount, order_type::Type, side::SideBS, stop_loss::StopLoss, take_profit::TakeProfit, time_in_force::TimeInForce},
 	},
 	bigdecimal::BigDecimal,
 	chrono::{DateTime, Utc},
 	serde::{Deserialize, Serialize},
+	std::fmt::{Display, Formatter, Result as FmtResult},
 	uuid::Uuid,
 };
 
@@ -53,9 +54,11 @@ impl Status {
 	}
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Class {
-	#[serde(rename = "simple")]
+	/// Sent as an empty string by the API.
+	#[serde(rename = "simple", alias = "")]
+	#[default]
 	Simple,
 	#[serde(rename = "bracket")]
 	Bracket,
@@ -65,44 +68,201 @@ pub enum Class {
 	OneTriggersOther,
 }
 
-impl Default for Class {
-	fn default() -> Self {
-		Self::Simple
-	}
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, bon::Builder)]
 pub struct OrderPostRequest {
-	pub symbol: Symbol,
+	/// A symbol or an asset ID.
+	#[builder(into)]
+	pub symbol: String,
 	#[serde(flatten)]
 	pub amount: Amount,
 	pub side: SideBS,
 	#[serde(rename = "order_class")]
+	#[builder(default)]
 	pub class: Class,
 	#[serde(rename = "type")]
+	#[builder(default)]
 	pub type_: Type,
+	#[builder(default)]
 	pub time_in_force: TimeInForce,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub limit_price: Option<BigDecimal>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub stop_price: Option<BigDecimal>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub trail_price: Option<BigDecimal>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub trail_percent: Option<BigDecimal>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub take_profit: Option<TakeProfit>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub stop_loss: Option<StopLoss>,
+	#[builder(default)]
 	pub extended_hours: bool,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[builder(into)]
 	pub client_order_id: Option<String>,
 }
 
+impl OrderPostRequest {
+	/// Check the rules the API would reject the order for, before sending it.
+	///
+	/// # Errors
+	///
+	/// Fails with the first rule the order breaks.
+	pub fn validate(&self) -> Result<(), OrderValidationError> {
+		let zero = BigDecimal::from(0);
+		let amount = match &self.amount {
+			Amount::Quantity { quantity } => quantity,
+			Amount::Notional { notional } => notional,
+		};
+		if *amount <= zero {
+			return Err(OrderValidationError::NonPositiveAmount);
+		}
+		let (type_, time_in_force) = (self.type_, self.time_in_force);
+		let (limit, stop) = match type_ {
+			Type::Market | Type::TrailingStop => (false, false),
+			Type::Limit => (true, false),
+			Type::Stop => (false, true),
+			Type::StopLimit => (true, true),
+		};
+		match (limit, &self.limit_price) {
+			(true, None) => return Err(OrderValidationError::MissingLimitPrice(type_)),
+			(false, Some(_)) => return Err(OrderValidationError::UnexpectedLimitPrice(type_)),
+			_ => {},
+		}
+		match (stop, &self.stop_price) {
+			(true, None) => return Err(OrderValidationError::MissingStopPrice(type_)),
+			(false, Some(_)) => return Err(OrderValidationError::UnexpectedStopPrice(type_)),
+			_ => {},
+		}
+		match (type_, &self.trail_price, &self.trail_percent) {
+			(Type::TrailingStop, Some(_), None) | (Type::TrailingStop, None, Some(_)) => {},
+			(Type::TrailingStop, _, _) => return Err(OrderValidationError::InvalidTrail),
+			(_, None, None) => {},
+			_ => return Err(OrderValidationError::UnexpectedTrail(type_)),
+		}
+		if [&self.limit_price, &self.stop_price, &self.trail_price, &self.trail_percent].into_iter().flatten().any(|price| *price <= zero) {
+			return Err(OrderValidationError::NonPositivePrice);
+		}
+		match (self.class, &self.take_profit, &self.stop_loss) {
+			(Class::Bracket | Class::OneCancelsOther, None, _) => return Err(OrderValidationError::MissingTakeProfit(self.class)),
+			(Class::Bracket | Class::OneCancelsOther, _, None) => return Err(OrderValidationError::MissingStopLoss(self.class)),
+			(Class::OneTriggersOther, None, None) => return Err(OrderValidationError::MissingLeg),
+			(Class::Simple, Some(_), _) | (Class::Simple, _, Some(_)) => return Err(OrderValidationError::UnexpectedLegs),
+			_ => {},
+		}
+		if matches!(self.amount, Amount::Notional { .. }) && (type_ != Type::Market || time_in_force != TimeInForce::Day) {
+			return Err(OrderValidationError::Notional { type_, time_in_force });
+		}
+		if self.extended_hours && (type_ != Type::Limit || time_in_force != TimeInForce::Day) {
+			return Err(OrderValidationError::ExtendedHours { type_, time_in_force });
+		}
+		Ok(())
+	}
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OrderPatchRequest {
-	#[serde(rename = "qty")]
+	#[serde(rename = "qty", skip_serializing_if = "Option::is_none")]
 	pub quantity: Option<BigDecimal>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub time_in_force: Option<TimeInForce>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub limit_price: Option<BigDecimal>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub stop_price: Option<BigDecimal>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub trail: Option<BigDecimal>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub client_order_id: Option<String>,
 }
 
+impl OrderPatchRequest {
+	/// # Errors
+	///
+	/// Fails when nothing is replaced or a quantity or price is not positive.
+	pub fn validate(&self) -> Result<(), OrderValidationError> {
+		if *self == Self::default() {
+			return Err(OrderValidationError::EmptyReplacement);
+		}
+		let zero = BigDecimal::from(0);
+		if self.quantity.as_ref().is_some_and(|quantity| *quantity <= zero) {
+			return Err(OrderValidationError::NonPositiveAmount);
+		}
+		if [&self.limit_price, &self.stop_price, &self.trail].into_iter().flatten().any(|price| *price <= zero) {
+			return Err(OrderValidationError::NonPositivePrice);
+		}
+		Ok(())
+	}
+}
+
+/// An order the API would reject, found before sending it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderValidationError {
+	NonPositiveAmount,
+	NonPositivePrice,
+	MissingLimitPrice(Type),
+	UnexpectedLimitPrice(Type),
+	MissingStopPrice(Type),
+	UnexpectedStopPrice(Type),
+	/// Trailing stops need either a trail price or a trail percent.
+	InvalidTrail,
+	UnexpectedTrail(Type),
+	MissingTakeProfit(Class),
+	MissingStopLoss(Class),
+	/// One-triggers-other orders need a take profit or a stop loss.
+	MissingLeg,
+	/// Simple orders have no take profit or stop loss.
+	UnexpectedLegs,
+	Notional {
+		type_: Type,
+		time_in_force: TimeInForce,
+	},
+	ExtendedHours {
+		type_: Type,
+		time_in_force: TimeInForce,
+	},
+	EmptyReplacement,
+}
+
+impl Display for OrderValidationError {
+	fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+		match self {
+			Self::NonPositiveAmount => fmt.write_str("the quantity or notional must be positive"),
+			Self::NonPositivePrice => fmt.write_str("prices must be positive"),
+			Self::MissingLimitPrice(type_) => write!(fmt, "{type_:?} orders need a limit price"),
+			Self::UnexpectedLimitPrice(type_) => write!(fmt, "{type_:?} orders take no limit price"),
+			Self::MissingStopPrice(type_) => write!(fmt, "{type_:?} orders need a stop price"),
+			Self::UnexpectedStopPrice(type_) => write!(fmt, "{type_:?} orders take no stop price"),
+			Self::InvalidTrail => fmt.write_str("trailing stop orders need either a trail price or a trail percent"),
+			Self::UnexpectedTrail(type_) => write!(fmt, "{type_:?} orders take no trail"),
+			Self::MissingTakeProfit(class) => write!(fmt, "{class:?} orders need a take profit"),
+			Self::MissingStopLoss(class) => write!(fmt, "{class:?} orders need a stop loss"),
+			Self::MissingLeg => fmt.write_str("one-triggers-other orders need a take profit or a stop loss"),
+			Self::UnexpectedLegs => fmt.write_str("simple orders take no take profit or stop loss"),
+			Self::Notional { type_, time_in_force } => write!(fmt, "notional orders must be Market and Day, not {type_:?} and {time_in_force:?}"),
+			Self::ExtendedHours { type_, time_in_force } => write!(fmt, "extended hours orders must be Limit and Day, not {type_:?} and {time_in_force:?}"),
+			Self::EmptyReplacement => fmt.write_str("the replacement changes nothing"),
+		}
+	}
+}
+
+impl std::error::Error for OrderValidationError {}
+
+/// An order of a cancel-all request, `status` is the HTTP status of its
+/// cancellation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CancelStatus {
+	pub id: Uuid,
+	pub status: u16,
+}
+
+impl CancelStatus {
+	pub fn is_success(&self) -> bool {
+		(200..300).contains(&self.status)
+	}
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OrderDTO {
 	pub id: Uuid,
@@ -114,7 +274,7 @@ pub struct OrderDTO {
 	pub filled_at: Option<DateTime<Utc>>,
 	pub expired_at: Option<DateTime<Utc>>,
 	pub canceled_at: Option<DateTime<Utc>>,
-	pub asset_class: Class,
+	pub asset_class: AssetClass,
 	pub asset_id: Uuid,
 	pub symbol: String,
 	#[serde(flatten)]
@@ -134,5 +294,123 @@ pub struct OrderDTO {
 	#[serde(rename = "filled_avg_price")]
 	pub average_fill_price: Option<BigDecimal>,
 	pub extended_hours: bool,
-	pub legs: Vec<OrderDTO>,
+	/// Only with `nested` requests, `None` for simple orders.
+	pub legs: Option<Vec<OrderDTO>>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn price(value: i32) -> Option<BigDecimal> {
+		Some(BigDecimal::from(value))
+	}
+
+	fn market() -> OrderPostRequest {
+		OrderPostRequest::builder().symbol("AAPL").amount(Amount::quantity(10)).side(SideBS::Buy).build()
+	}
+
+	#[test]
+	fn orders_break_the_first_rule_they_fail() {
+		let cases = [
+			(OrderPostRequest { amount: Amount::quantity(0), ..market() }, OrderValidationError::NonPositiveAmount, "the quantity or notional must be positive"),
+			(OrderPostRequest { type_: Type::Limit, ..market() }, OrderValidationError::MissingLimitPrice(Type::Limit), "Limit orders need a limit price"),
+			(OrderPostRequest { limit_price: price(100), ..market() }, OrderValidationError::UnexpectedLimitPrice(Type::Market), "Market orders take no limit price"),
+			(OrderPostRequest { type_: Type::Stop, ..market() }, OrderValidationError::MissingStopPrice(Type::Stop), "Stop orders need a stop price"),
+			(
+				OrderPostRequest { type_: Type::StopLimit, limit_price: price(100), ..market() },
+				OrderValidationError::MissingStopPrice(Type::StopLimit),
+				"StopLimit orders need a stop price",
+			),
+			(
+				OrderPostRequest { type_: Type::Limit, limit_price: price(100), stop_price: price(90), ..market() },
+				OrderValidationError::UnexpectedStopPrice(Type::Limit),
+				"Limit orders take no stop price",
+			),
+			(
+				OrderPostRequest { type_: Type::TrailingStop, ..market() },
+				OrderValidationError::InvalidTrail,
+				"trailing stop orders need either a trail price or a trail percent",
+			),
+			(
+				OrderPostRequest { type_: Type::TrailingStop, trail_price: price(5), trail_percent: price(1), ..market() },
+				OrderValidationError::InvalidTrail,
+				"trailing stop orders need either a trail price or a trail percent",
+			),
+			(OrderPostRequest { trail_percent: price(1), ..market() }, OrderValidationError::UnexpectedTrail(Type::Market), "Market orders take no trail"),
+			(OrderPostRequest { type_: Type::Limit, limit_price: price(-1), ..market() }, OrderValidationError::NonPositivePrice, "prices must be positive"),
+			(
+				OrderPostRequest { class: Class::Bracket, stop_loss: Some(StopLoss::Stop(BigDecimal::from(90))), ..market() },
+				OrderValidationError::MissingTakeProfit(Class::Bracket),
+				"Bracket orders need a take profit",
+			),
+			(
+				OrderPostRequest { class: Class::Bracket, take_profit: Some(TakeProfit::Limit(BigDecimal::from(110))), ..market() },
+				OrderValidationError::MissingStopLoss(Class::Bracket),
+				"Bracket orders need a stop loss",
+			),
+			(
+				OrderPostRequest { class: Class::OneTriggersOther, ..market() },
+				OrderValidationError::MissingLeg,
+				"one-triggers-other orders need a take profit or a stop loss",
+			),
+			(
+				OrderPostRequest { take_profit: Some(TakeProfit::Limit(BigDecimal::from(110))), ..market() },
+				OrderValidationError::UnexpectedLegs,
+				"simple orders take no take profit or stop loss",
+			),
+			(
+				OrderPostRequest { amount: Amount::notional(500), type_: Type::Limit, limit_price: price(100), ..market() },
+				OrderValidationError::Notional { type_: Type::Limit, time_in_force: TimeInForce::Day },
+				"notional orders must be Market and Day, not Limit and Day",
+			),
+			(
+				OrderPostRequest { amount: Amount::notional(500), time_in_force: TimeInForce::UntilCanceled, ..market() },
+				OrderValidationError::Notional { type_: Type::Market, time_in_force: TimeInForce::UntilCanceled },
+				"notional orders must be Market and Day, not Market and UntilCanceled",
+			),
+			(
+				OrderPostRequest { extended_hours: true, ..market() },
+				OrderValidationError::ExtendedHours { type_: Type::Market, time_in_force: TimeInForce::Day },
+				"extended hours orders must be Limit and Day, not Market and Day",
+			),
+			(
+				OrderPostRequest { extended_hours: true, type_: Type::Limit, limit_price: price(100), time_in_force: TimeInForce::UntilCanceled, ..market() },
+				OrderValidationError::ExtendedHours { type_: Type::Limit, time_in_force: TimeInForce::UntilCanceled },
+				"extended hours orders must be Limit and Day, not Limit and UntilCanceled",
+			),
+		];
+		for (order, expected, message) in cases {
+			assert_eq!(order.validate(), Err(expected), "{order:?}");
+			assert_eq!(expected.to_string(), message);
+		}
+	}
+
+	#[test]
+	fn valid_orders_pass() {
+		let take_profit = Some(TakeProfit::Limit(BigDecimal::from(110)));
+		let stop_loss = Some(StopLoss::StopLimit(BigDecimal::from(90), BigDecimal::from(89)));
+		let orders = [
+			market(),
+			OrderPostRequest { amount: Amount::notional(500), ..market() },
+			OrderPostRequest { type_: Type::StopLimit, limit_price: price(100), stop_price: price(101), ..market() },
+			OrderPostRequest { type_: Type::TrailingStop, trail_percent: price(2), time_in_force: TimeInForce::UntilCanceled, ..market() },
+			OrderPostRequest { extended_hours: true, type_: Type::Limit, limit_price: price(100), ..market() },
+			OrderPostRequest { class: Class::Bracket, take_profit: take_profit.clone(), stop_loss: stop_loss.clone(), ..market() },
+			OrderPostRequest { class: Class::OneTriggersOther, stop_loss, ..market() },
+		];
+		for order in orders {
+			assert_eq!(order.validate(), Ok(()), "{order:?}");
+		}
+	}
+
+	#[test]
+	fn replacements_change_something_positive() {
+		assert_eq!(OrderPatchRequest::default().validate(), Err(OrderValidationError::EmptyReplacement));
+		assert_eq!(OrderValidationError::EmptyReplacement.to_string(), "the replacement changes nothing");
+		assert_eq!(OrderPatchRequest { quantity: price(0), ..OrderPatchRequest::default() }.validate(), Err(OrderValidationError::NonPositiveAmount));
+		assert_eq!(OrderPatchRequest { trail: price(-2), ..OrderPatchRequest::default() }.validate(), Err(OrderValidationError::NonPositivePrice));
+		assert_eq!(OrderPatchRequest { limit_price: price(100), ..OrderPatchRequest::default() }.validate(), Ok(()));
+		assert_eq!(OrderPatchRequest { client_order_id: Some("mine".to_owned()), ..OrderPatchRequest::default() }.validate(), Ok(()));
+	}
 }
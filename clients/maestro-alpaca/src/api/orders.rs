@@ -1,12 +1,16 @@
 use {
-	super::order::OrderDTO,
-	crate::{
-		alpaca_env::AlpacaUrls,
-		routes::{EAlpacaRoute, EApiRoute},
-	},
-	reqwest::Client,
+	super::enums::direction::Direction,
+	crate::data::last_quotes::last_quotes_dtos::serialize_vec_to_csv,
+	chrono::{DateTime, Utc},
 	serde::{Deserialize, Serialize},
 };
+#[cfg(feature = "server")]
+use {
+	super::order::{CancelStatus, OrderDTO, OrderPatchRequest, OrderPostRequest},
+	crate::{error::AlpacaResult, get_client::AlpacaClient, routes::EApiRoute},
+	reqwest::Method,
+	uuid::Uuid,
+};
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Status {
@@ -21,25 +25,70 @@ pub enum Status {
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OrdersGetRequest {
+	#[serde(serialize_with = "serialize_vec_to_csv", skip_serializing_if = "Vec::is_empty")]
 	pub symbols: Vec<String>,
 	pub status: Status,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub limit: Option<usize>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub after: Option<DateTime<Utc>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub until: Option<DateTime<Utc>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub direction: Option<Direction>,
+	/// Roll up the legs of multi-leg orders under `legs`.
 	pub nested: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-struct OrdersDTO {
-	orders: Vec<OrderDTO>,
-}
+#[cfg(feature = "server")]
+#[bon::bon]
+impl AlpacaClient {
+	/// Orders of every symbol when `symbols` is empty.
+	#[builder]
+	pub async fn orders(
+		&self,
+		#[builder(default)] symbols: Vec<String>,
+		#[builder(default)] status: Status,
+		limit: Option<usize>,
+		after: Option<DateTime<Utc>>,
+		until: Option<DateTime<Utc>>,
+		direction: Option<Direction>,
+		#[builder(default)] nested: bool,
+	) -> AlpacaResult<Vec<OrderDTO>> {
+		let query = OrdersGetRequest { symbols, status, limit, after, until, direction, nested };
+		self.send(self.api(Method::GET, EApiRoute::Orders).query(&query)).await
+	}
+
+	pub async fn order(&self, id: Uuid) -> AlpacaResult<OrderDTO> {
+		self.send(self.api(Method::GET, EApiRoute::Order(id))).await
+	}
+
+	/// # Errors
+	///
+	/// Fails without sending the order when [`OrderPostRequest::validate`]
+	/// does.
+	pub async fn submit_order(&self, order: &OrderPostRequest) -> AlpacaResult<OrderDTO> {
+		order.validate()?;
+		self.send(self.api(Method::POST, EApiRoute::Orders).json(order)).await
+	}
+
+	/// Replace an open order, the returned order is the new one.
+	///
+	/// # Errors
+	///
+	/// Fails without sending the request when [`OrderPatchRequest::validate`]
+	/// does.
+	pub async fn replace_order(&self, id: Uuid, patch: &OrderPatchRequest) -> AlpacaResult<OrderDTO> {
+		patch.validate()?;
+		self.send(self.api(Method::PATCH, EApiRoute::Order(id)).json(patch)).await
+	}
+
+	pub async fn cancel_order(&self, id: Uuid) -> AlpacaResult<()> {
+		self.send_empty(self.api(Method::DELETE, EApiRoute::Order(id))).await
+	}
 
-#[bon::builder]
-pub async fn orders_get_request(
-	urls: AlpacaUrls,
-	symbols: Vec<String>,
-	limit: Option<usize>,
-	nested: bool,
-	#[builder(default)] status: Status,
-	#[builder(default = Client::new())] client: Client,
-) -> Result<OrdersDTO, reqwest::Error> {
-	client.get(EAlpacaRoute::Api(EApiRoute::Orders).url_path(urls)).query(&OrdersGetRequest { symbols, status, limit, nested }).send().await?.json().await
+	/// Cancel every open order, some cancellations may fail.
+	pub async fn cancel_all_orders(&self) -> AlpacaResult<Vec<CancelStatus>> {
+		self.send(self.api(Method::DELETE, EApiRoute::Orders)).await
+	}
 }
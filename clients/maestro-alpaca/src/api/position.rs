@@ -7,6 +7,23 @@ use {
 	serde::{Deserialize, Serialize},
 	uuid::Uuid,
 };
+#[cfg(feature = "server")]
+use {
+	crate::{error::AlpacaResult, get_client::AlpacaClient, routes::EApiRoute},
+	reqwest::Method,
+};
+
+#[cfg(feature = "server")]
+impl AlpacaClient {
+	pub async fn positions(&self) -> AlpacaResult<Vec<Position>> {
+		self.send(self.api(Method::GET, EApiRoute::Positions)).await
+	}
+
+	/// The open position of a symbol or asset ID.
+	pub async fn position(&self, symbol: &str) -> AlpacaResult<Position> {
+		self.send(self.api(Method::GET, EApiRoute::Position(symbol.to_owned()))).await
+	}
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Position {
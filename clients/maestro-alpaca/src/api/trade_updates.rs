@@ -0,0 +1,141 @@
+use {
+	super::enums::updates::{Authenticate, Authentication, AuthenticationStatus, Listen, OrderUpdate, OrderUpdates, StreamType},
+	crate::{
+		error::{AlpacaError, AlpacaResult},
+		get_client::AlpacaClient,
+		routes::EStreamRoute,
+		websocket::{self, ReconnectPolicy, Socket},
+	},
+	futures::Stream,
+	std::{
+		pin::Pin,
+		task::{Context, Poll},
+	},
+	tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel},
+};
+#[cfg(feature = "dioxus")]
+use {dioxus::prelude::*, futures::StreamExt};
+
+/// Updates of the account's orders. The connection runs in a task that stops
+/// when the stream is dropped or fails for good, which is the last item.
+/// Updates sent while reconnecting are lost, fetch the orders again to catch
+/// up.
+#[derive(Debug)]
+pub struct OrderUpdateStream {
+	items: UnboundedReceiver<AlpacaResult<OrderUpdate>>,
+}
+
+impl Stream for OrderUpdateStream {
+	type Item = AlpacaResult<OrderUpdate>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		self.items.poll_recv(cx)
+	}
+}
+
+#[bon::bon]
+impl AlpacaClient {
+	/// Connect to the `trade_updates` stream of the trading API. Must be called
+	/// within a Tokio runtime.
+	#[builder(finish_fn = connect)]
+	pub fn order_updates(&self, #[builder(default)] reconnect: ReconnectPolicy) -> OrderUpdateStream {
+		let (items_tx, items) = unbounded_channel();
+		let connection = Connection { url: self.url(EStreamRoute::TradeUpdates), auth: self.authenticate(), reconnect, items: items_tx };
+		tokio::spawn(connection.run());
+		OrderUpdateStream { items }
+	}
+}
+
+struct Connection {
+	url: String,
+	auth: Authenticate<'static>,
+	reconnect: ReconnectPolicy,
+	items: UnboundedSender<AlpacaResult<OrderUpdate>>,
+}
+
+impl Connection {
+	async fn run(self) {
+		let mut attempt = 0;
+		loop {
+			let error = match self.session(&mut attempt).await {
+				Ok(()) => return,
+				Err(error) => error,
+			};
+			let Some(delay) = (!error.is_fatal()).then(|| self.reconnect.delay(attempt)).flatten() else {
+				let _ = self.items.send(Err(error));
+				return;
+			};
+			attempt += 1;
+			tokio::select! {
+				() = self.items.closed() => return,
+				() = tokio::time::sleep(delay) => {},
+			}
+		}
+	}
+
+	/// Connect, authenticate, listen and forward updates. Returns `Ok` once the
+	/// stream is dropped.
+	async fn session(&self, attempt: &mut u32) -> AlpacaResult<()> {
+		let mut socket = websocket::connect(&self.url).await?;
+		websocket::send_json(&mut socket, &self.auth).await?;
+		match next_message(&mut socket).await? {
+			OrderUpdates::Authorization(Authentication { status: AuthenticationStatus::Authorized, .. }) => {},
+			OrderUpdates::Authorization(Authentication { message, .. }) =>
+				return Err(AlpacaError::Unauthorized { message: message.unwrap_or_else(|| "authentication failed".to_owned()) }),
+			_ => return Err(AlpacaError::Unauthorized { message: "no reply to the authentication".to_owned() }),
+		}
+		websocket::send_json(&mut socket, &Listen::Request { streams: vec![StreamType::OrderUpdates] }).await?;
+		*attempt = 0;
+		loop {
+			tokio::select! {
+				() = self.items.closed() => return Ok(()),
+				message = next_message(&mut socket) => match message {
+					Ok(OrderUpdates::Update(update)) => {
+						let _ = self.items.send(Ok(*update));
+					},
+					Ok(_) => {},
+					Err(error @ AlpacaError::Decode(_)) => {
+						let _ = self.items.send(Err(error));
+					},
+					Err(error) => return Err(error),
+				},
+			}
+		}
+	}
+}
+
+/// The next message, sent as binary frames by the paper trading API and as
+/// text by the live one.
+async fn next_message(socket: &mut Socket) -> AlpacaResult<OrderUpdates> {
+	Ok(serde_json::from_slice(&websocket::next_frame(socket).await?)?)
+}
+
+/// Order updates received by [`use_order_updates`], oldest first.
+#[cfg(feature = "dioxus")]
+#[derive(Clone, Copy, PartialEq)]
+pub struct UseOrderUpdates {
+	pub updates: Signal<Vec<OrderUpdate>>,
+	/// The last error, the stream stops after a fatal one.
+	pub error: Signal<Option<String>>,
+}
+
+/// Bridge [`AlpacaClient::order_updates`] into signals, for components
+/// running with the server feature, like desktop apps. The stream stops with
+/// the component.
+#[cfg(feature = "dioxus")]
+pub fn use_order_updates(client: AlpacaClient) -> UseOrderUpdates {
+	let mut updates = use_signal(Vec::new);
+	let mut error = use_signal(|| None);
+	use_future(move || {
+		let mut stream = client.order_updates().connect();
+		async move {
+			while let Some(item) = stream.next().await {
+				match item {
+					Ok(update) => updates.write().push(update),
+					Err(err) => error.set(Some(err.to_string())),
+				}
+			}
+		}
+	});
+	UseOrderUpdates { updates, error }
+}
@@ -4,14 +4,48 @@ use {
 	serde::{Deserialize, Serialize},
 	uuid::Uuid,
 };
+#[cfg(feature = "server")]
+use {
+	crate::{error::AlpacaResult, get_client::AlpacaClient, routes::EApiRoute},
+	reqwest::Method,
+};
+
+#[cfg(feature = "server")]
+impl AlpacaClient {
+	pub async fn create_watchlist(&self, watchlist: &UpdateReq) -> AlpacaResult<Watchlist> {
+		self.send(self.api(Method::POST, EApiRoute::Watchlists).json(watchlist)).await
+	}
+
+	pub async fn watchlist(&self, id: Uuid) -> AlpacaResult<Watchlist> {
+		self.send(self.api(Method::GET, EApiRoute::Watchlist(id))).await
+	}
+
+	/// Replace the name and the symbols of a watchlist.
+	pub async fn update_watchlist(&self, id: Uuid, watchlist: &UpdateReq) -> AlpacaResult<Watchlist> {
+		self.send(self.api(Method::PUT, EApiRoute::Watchlist(id)).json(watchlist)).await
+	}
+
+	pub async fn delete_watchlist(&self, id: Uuid) -> AlpacaResult<()> {
+		self.send_empty(self.api(Method::DELETE, EApiRoute::Watchlist(id))).await
+	}
+
+	pub async fn add_to_watchlist(&self, id: Uuid, symbol: &str) -> AlpacaResult<Watchlist> {
+		self.send(self.api(Method::POST, EApiRoute::Watchlist(id)).json(&serde_json::json!({ "symbol": symbol }))).await
+	}
+
+	pub async fn remove_from_watchlist(&self, id: Uuid, symbol: &str) -> AlpacaResult<Watchlist> {
+		self.send(self.api(Method::DELETE, EApiRoute::WatchlistSymbol(id, symbol.to_owned()))).await
+	}
+}
 
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 pub struct Watchlist {
 	pub id: Uuid,
 	pub name: String,
 	pub account_id: Uuid,
 	pub created_at: DateTime<Utc>,
 	pub updated_at: DateTime<Utc>,
+	#[serde(default)]
 	pub assets: Vec<Asset>,
 }
 
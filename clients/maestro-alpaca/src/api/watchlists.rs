@@ -1,9 +1,22 @@
+#[cfg(feature = "server")]
+use {
+	crate::{error::AlpacaResult, get_client::AlpacaClient, routes::EApiRoute},
+	reqwest::Method,
+};
 use {
 	chrono::{DateTime, Utc},
 	serde::Deserialize,
 	uuid::Uuid,
 };
 
+#[cfg(feature = "server")]
+impl AlpacaClient {
+	/// The watchlists of the account, without their assets.
+	pub async fn watchlists(&self) -> AlpacaResult<Vec<WatchlistItem>> {
+		self.send(self.api(Method::GET, EApiRoute::Watchlists)).await
+	}
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 pub struct WatchlistItem {
 	pub id: Uuid,
@@ -0,0 +1,5 @@
+pub mod report;
+pub mod strategy;
+
+#[cfg(feature = "server")]
+pub mod runner;
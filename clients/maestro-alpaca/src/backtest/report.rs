@@ -0,0 +1,136 @@
+use {
+	crate::api::enums::side::SideBS,
+	bigdecimal::BigDecimal,
+	chrono::{DateTime, Utc},
+	serde::{Deserialize, Serialize},
+	uuid::Uuid,
+};
+
+const SECONDS_PER_DAY: f32 = 86_400.0;
+
+/// The outcome of a backtest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+	pub initial_equity: f64,
+	/// The equity after each bar time, oldest first.
+	pub equity_curve: Vec<EquityPoint>,
+	/// The largest fall from a peak, as a fraction of the peak.
+	pub max_drawdown: f64,
+	/// Annualized, without risk-free rate. `None` with less than two returns
+	/// or constant ones.
+	pub sharpe: Option<f64>,
+	/// The fills, oldest first.
+	pub trades: Vec<Trade>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EquityPoint {
+	pub time: DateTime<Utc>,
+	pub equity: f64,
+	/// The fraction below the highest equity so far.
+	pub drawdown: f64,
+}
+
+/// A filled order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trade {
+	pub order_id: Uuid,
+	pub time: DateTime<Utc>,
+	pub symbol: String,
+	pub side: SideBS,
+	pub quantity: BigDecimal,
+	pub price: BigDecimal,
+	pub commission: BigDecimal,
+}
+
+impl Report {
+	/// Compute the statistics of an equity curve, `periods_per_year` being
+	/// the number of its points in a year.
+	pub fn new(initial_equity: f64, curve: &[(DateTime<Utc>, f64)], trades: Vec<Trade>, periods_per_year: f64) -> Self {
+		let mut peak = initial_equity;
+		let equity_curve = curve
+			.iter()
+			.map(|&(time, equity)| {
+				peak = peak.max(equity);
+				let drawdown = if peak > 0.0 { (peak - equity) / peak } else { 0.0 };
+				EquityPoint { time, equity, drawdown }
+			})
+			.collect::<Vec<_>>();
+		let max_drawdown = equity_curve.iter().map(|point| point.drawdown).fold(0.0, f64::max);
+		let equities = std::iter::once(initial_equity).chain(curve.iter().map(|&(_, equity)| equity)).collect::<Vec<_>>();
+		let returns = equities.windows(2).filter(|pair| pair[0] != 0.0).map(|pair| pair[1] / pair[0] - 1.0).collect::<Vec<_>>();
+		Self { initial_equity, equity_curve, max_drawdown, sharpe: sharpe(&returns, periods_per_year), trades }
+	}
+
+	pub fn final_equity(&self) -> f64 {
+		self.equity_curve.last().map_or(self.initial_equity, |point| point.equity)
+	}
+
+	/// The gain as a fraction of the initial equity.
+	pub fn total_return(&self) -> f64 {
+		self.final_equity() / self.initial_equity - 1.0
+	}
+
+	/// The equity over the days since the first point, as the single series
+	/// `maestro_plotters::line_map::render_line_chart` takes.
+	pub fn equity_chart(&self) -> Vec<Vec<(f32, f32)>> {
+		vec![self.chart(|point| point.equity)]
+	}
+
+	/// The drawdown over the days since the first point, like
+	/// [`equity_chart`](Self::equity_chart).
+	pub fn drawdown_chart(&self) -> Vec<Vec<(f32, f32)>> {
+		vec![self.chart(|point| point.drawdown)]
+	}
+
+	#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+	fn chart(&self, value: impl Fn(&EquityPoint) -> f64) -> Vec<(f32, f32)> {
+		let Some(start) = self.equity_curve.first().map(|point| point.time) else {
+			return Vec::new();
+		};
+		self.equity_curve.iter().map(|point| ((point.time - start).num_seconds() as f32 / SECONDS_PER_DAY, value(point) as f32)).collect()
+	}
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn sharpe(returns: &[f64], periods_per_year: f64) -> Option<f64> {
+	if returns.len() < 2 {
+		return None;
+	}
+	let count = returns.len() as f64;
+	let mean = returns.iter().sum::<f64>() / count;
+	let deviation = (returns.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / (count - 1.0)).sqrt();
+	(deviation > 0.0).then(|| mean / deviation * periods_per_year.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, chrono::TimeZone};
+
+	fn day(day: u32) -> DateTime<Utc> {
+		Utc.with_ymd_and_hms(2024, 1, day, 21, 0, 0).unwrap()
+	}
+
+	#[test]
+	fn statistics_follow_the_curve() {
+		let report = Report::new(100.0, &[(day(2), 110.0), (day(3), 99.0), (day(4), 121.0)], Vec::new(), 252.0);
+		let drawdowns = report.equity_curve.iter().map(|point| point.drawdown).collect::<Vec<_>>();
+		assert_eq!(drawdowns, [0.0, 0.1, 0.0]);
+		assert!((report.max_drawdown - 0.1).abs() < 1e-12);
+		// Returns of 10%, -10% and 22.2%: a mean of 7.41% and a sample
+		// deviation of 16.27%, annualized over 252 periods.
+		assert!((report.sharpe.unwrap() - 7.2288).abs() < 1e-4, "{:?}", report.sharpe);
+		assert!((report.total_return() - 0.21).abs() < 1e-12);
+		assert_eq!(report.equity_chart(), [vec![(0.0, 110.0), (1.0, 99.0), (2.0, 121.0)]]);
+		assert_eq!(report.drawdown_chart()[0].len(), 3);
+	}
+
+	#[test]
+	fn flat_curves_have_no_sharpe() {
+		let report = Report::new(100.0, &[(day(2), 100.0), (day(3), 100.0), (day(4), 100.0)], Vec::new(), 252.0);
+		assert_eq!(report.sharpe, None);
+		assert_eq!(report.max_drawdown, 0.0);
+		assert_eq!(Report::new(100.0, &[(day(2), 105.0)], Vec::new(), 252.0).sharpe, None);
+		assert!(Report::new(100.0, &[], Vec::new(), 252.0).equity_chart()[0].is_empty());
+	}
+}
@@ -0,0 +1,207 @@
+use {
+	super::{
+		report::{Report, Trade},
+		strategy::{Context, Strategy},
+	},
+	crate::{
+		api::{
+			enums::{order_status::OrderStatus, updates::OrderUpdate},
+			orders,
+		},
+		broker::{Broker, fill_model::FillModel, simulated::SimulatedBroker},
+		data::{
+			bars::{bars_dtos::NewBar, bars_reqwest::bars_stream_multi},
+			enums::{adjustment::Adjustment, feed::Feed, timeframe::TimeFrame},
+			pagination::group_by_symbol,
+		},
+		db::bars::bars_range,
+		error::AlpacaResult,
+		get_client::AlpacaClient,
+	},
+	bigdecimal::{BigDecimal, ToPrimitive},
+	chrono::{DateTime, Utc},
+	maestro_diesel::async_client::AsyncDieselPool,
+	std::collections::HashMap,
+};
+
+/// Replays bars through a [`Strategy`] on a [`SimulatedBroker`].
+#[derive(Debug, Clone, bon::Builder)]
+pub struct Backtest {
+	#[builder(into)]
+	pub cash: BigDecimal,
+	#[builder(default)]
+	pub fill_model: FillModel,
+	#[builder(default = true)]
+	pub shorting_enabled: bool,
+	/// The bars in a year, to annualize the Sharpe ratio: 252 for daily
+	/// bars, 252 × 390 for minute bars of regular hours.
+	#[builder(default = 252.0)]
+	pub periods_per_year: f64,
+}
+
+impl Backtest {
+	/// Run `strategy` over the bars of each symbol, oldest first. Bars of
+	/// different symbols at the same time are handled together.
+	///
+	/// # Errors
+	///
+	/// Fails when the strategy returns an order the simulated broker does not
+	/// take.
+	pub async fn run<S: Strategy + ?Sized>(&self, strategy: &mut S, bars: &HashMap<String, Vec<NewBar>>) -> AlpacaResult<Report> {
+		let mut timeline =
+			bars.iter().flat_map(|(symbol, bars)| bars.iter().enumerate().map(move |(index, bar)| (bar.time, symbol.as_str(), index))).collect::<Vec<_>>();
+		timeline.sort_unstable();
+		let start = timeline.first().map_or_else(Utc::now, |&(time, ..)| time);
+		let broker =
+			SimulatedBroker::builder().cash(self.cash.clone()).now(start).shorting_enabled(self.shorting_enabled).fill_model(self.fill_model.clone()).build();
+		let mut curve = Vec::new();
+		let mut trades = Vec::new();
+		for step in timeline.chunk_by(|(a, ..), (b, ..)| a == b) {
+			let time = step[0].0;
+			for &(_, symbol, index) in step {
+				for update in broker.on_bar(symbol, &bars[symbol][index]).await {
+					trades.extend(self.trade(&update));
+					strategy.on_order_update(&update);
+				}
+			}
+			let account = broker.account().await?;
+			let positions = broker.positions().await?;
+			let open_orders = broker.orders(orders::Status::Open).await?;
+			curve.push((time, to_f64(&account.equity)));
+			for &(_, symbol, index) in step {
+				let history = &bars[symbol][..=index];
+				let context = Context { time, account: &account, positions: &positions, open_orders: &open_orders, history };
+				for order in strategy.on_bar(&context, symbol, &history[index]) {
+					broker.submit_order(&order).await?;
+				}
+			}
+		}
+		Ok(Report::new(to_f64(&self.cash), &curve, trades, self.periods_per_year))
+	}
+
+	fn trade(&self, update: &OrderUpdate) -> Option<Trade> {
+		let order = &update.order;
+		let (OrderStatus::Filled, Some(price), Some(time)) = (update.event, &order.average_fill_price, order.filled_at) else {
+			return None;
+		};
+		Some(Trade {
+			order_id: order.id,
+			time,
+			symbol: order.symbol.clone(),
+			side: order.side,
+			commission: self.fill_model.commission(&order.filled_quantity, price),
+			quantity: order.filled_quantity.clone(),
+			price: price.clone(),
+		})
+	}
+}
+
+/// Bars of `symbols` from the API, by symbol.
+///
+/// # Errors
+///
+/// Fails with the first failing page.
+#[bon::builder]
+pub async fn bars_from_api(
+	client: AlpacaClient,
+	symbols: Vec<String>,
+	start: DateTime<Utc>,
+	end: Option<DateTime<Utc>>,
+	adjustment: Option<Adjustment>,
+	#[builder(default)] timeframe: TimeFrame,
+	#[builder(default)] feed: Feed,
+) -> AlpacaResult<HashMap<String, Vec<NewBar>>> {
+	let bars = bars_stream_multi()
+		.client(client)
+		.symbols(symbols)
+		.limit(10_000)
+		.start(start)
+		.maybe_end(end)
+		.maybe_adjustment(adjustment)
+		.timeframe(timeframe)
+		.feed(feed)
+		.call();
+	group_by_symbol(bars).await
+}
+
+/// Bars of `symbols` stored by [`upsert_bars`](crate::db::bars::upsert_bars),
+/// from `start`, inclusive, to `end`, exclusive.
+///
+/// # Errors
+///
+/// Fails when no connection is available or a query fails.
+pub async fn bars_from_cache(
+	pool: AsyncDieselPool,
+	symbols: &[String],
+	timeframe: TimeFrame,
+	start: DateTime<Utc>,
+	end: DateTime<Utc>,
+) -> AlpacaResult<HashMap<String, Vec<NewBar>>> {
+	let mut bars = HashMap::new();
+	for symbol in symbols {
+		bars.insert(symbol.clone(), bars_range(pool.clone(), symbol, timeframe, start, end).await?);
+	}
+	Ok(bars)
+}
+
+fn to_f64(value: &BigDecimal) -> f64 {
+	value.to_f64().unwrap_or(f64::NAN)
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		crate::api::{
+			enums::{amount::Amount, side::SideBS},
+			order::OrderPostRequest,
+		},
+		chrono::TimeZone,
+		std::str::FromStr,
+	};
+
+	/// Buys 10 shares on the first bar and sells them on the second.
+	struct BuyThenSell;
+
+	impl Strategy for BuyThenSell {
+		fn on_bar(&mut self, context: &Context<'_>, symbol: &str, _bar: &NewBar) -> Vec<OrderPostRequest> {
+			let side = match context.history.len() {
+				1 => SideBS::Buy,
+				2 => SideBS::Sell,
+				_ => return Vec::new(),
+			};
+			vec![OrderPostRequest::builder().symbol(symbol).amount(Amount::quantity(10)).side(side).build()]
+		}
+	}
+
+	fn bar(day: u32, open: f32, close: f32) -> NewBar {
+		let time = Utc.with_ymd_and_hms(2024, 1, day, 14, 30, 0).unwrap();
+		NewBar { time, open, high: open.max(close), low: open.min(close), close, volume: 1000, trade_count: 10, weighted_average: close }
+	}
+
+	fn number(value: &str) -> BigDecimal {
+		BigDecimal::from_str(value).unwrap()
+	}
+
+	#[tokio::test]
+	async fn orders_fill_from_the_next_bar() {
+		let bars = HashMap::from([("AAPL".to_owned(), vec![bar(2, 100.0, 100.0), bar(3, 102.0, 104.0), bar(4, 110.0, 108.0)])]);
+		let fill_model = FillModel::builder().slippage(number("0.01")).commission_per_order(1).build();
+		let backtest = Backtest::builder().cash(10_000).fill_model(fill_model).build();
+		let report = backtest.run(&mut BuyThenSell, &bars).await.unwrap();
+
+		let trades =
+			report.trades.iter().map(|trade| (trade.time, trade.side, trade.quantity.clone(), trade.price.clone(), trade.commission.clone())).collect::<Vec<_>>();
+		assert_eq!(
+			trades,
+			[
+				(bars["AAPL"][1].time, SideBS::Buy, number("10"), number("103.02"), number("1")),
+				(bars["AAPL"][2].time, SideBS::Sell, number("10"), number("108.9"), number("1")),
+			]
+		);
+		// 10 000 - 1030.2 - 1, plus 10 shares at the close of 104.
+		assert!((report.equity_curve[1].equity - 10_008.8).abs() < 1e-9);
+		// 10 000 - 1030.2 - 1 + 1089 - 1.
+		assert!((report.final_equity() - 10_056.8).abs() < 1e-9);
+	}
+}
@@ -0,0 +1,40 @@
+use {
+	crate::{
+		api::{
+			account::Account,
+			enums::updates::OrderUpdate,
+			order::{OrderDTO, OrderPostRequest},
+			position::Position,
+		},
+		data::bars::bars_dtos::NewBar,
+	},
+	chrono::{DateTime, Utc},
+};
+
+/// Trading logic driven by bars.
+pub trait Strategy {
+	/// Called with each bar, once every symbol's bar of that time has been
+	/// checked against the open orders. The returned orders are checked from
+	/// the next bar of their symbol on.
+	fn on_bar(&mut self, context: &Context<'_>, symbol: &str, bar: &NewBar) -> Vec<OrderPostRequest>;
+
+	/// Called with the fills, cancellations and rejections of the orders.
+	fn on_order_update(&mut self, _update: &OrderUpdate) {}
+}
+
+/// The state of the account at a bar, before the strategy's orders for it.
+#[derive(Debug, Clone, Copy)]
+pub struct Context<'c> {
+	pub time: DateTime<Utc>,
+	pub account: &'c Account,
+	pub positions: &'c [Position],
+	pub open_orders: &'c [OrderDTO],
+	/// The bars of the symbol so far, ending with the current one.
+	pub history: &'c [NewBar],
+}
+
+impl Context<'_> {
+	pub fn position(&self, symbol: &str) -> Option<&Position> {
+		self.positions.iter().find(|position| position.symbol == symbol)
+	}
+}
@@ -0,0 +1,38 @@
+use {
+	crate::api::enums::side::SideBS,
+	bigdecimal::{BigDecimal, One},
+};
+
+/// The costs [`SimulatedBroker`](super::simulated::SimulatedBroker) adds to
+/// its fills, none by default.
+#[derive(Debug, Default, Clone, PartialEq, Eq, bon::Builder)]
+pub struct FillModel {
+	/// The fraction of the price market and stop orders pay above it when
+	/// buying and get below it when selling, `0.0005` being 5 basis points.
+	/// Limit prices are kept.
+	#[builder(into, default)]
+	pub slippage: BigDecimal,
+	#[builder(into, default)]
+	pub commission_per_order: BigDecimal,
+	/// Per share, or per coin.
+	#[builder(into, default)]
+	pub commission_per_unit: BigDecimal,
+	/// The fraction of the traded value.
+	#[builder(into, default)]
+	pub commission_rate: BigDecimal,
+}
+
+impl FillModel {
+	/// `price` moved against `side` by the slippage.
+	pub fn slipped(&self, side: SideBS, price: &BigDecimal) -> BigDecimal {
+		match side {
+			SideBS::Buy => price * (BigDecimal::one() + &self.slippage),
+			SideBS::Sell => price * (BigDecimal::one() - &self.slippage),
+		}
+	}
+
+	/// The commission of a fill, `price` being the slipped one.
+	pub fn commission(&self, quantity: &BigDecimal, price: &BigDecimal) -> BigDecimal {
+		&self.commission_per_order + quantity * &self.commission_per_unit + quantity * price * &self.commission_rate
+	}
+}
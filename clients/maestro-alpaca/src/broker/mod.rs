@@ -0,0 +1,97 @@
+//! The trading endpoints behind a trait, so order logic runs the same against
+//! the API and against [`simulated::SimulatedBroker`].
+
+pub mod fill_model;
+pub mod simulated;
+
+use {
+	crate::{
+		api::{
+			account::Account,
+			clock::ClockDTO,
+			enums::updates::OrderUpdate,
+			order::{CancelStatus, OrderDTO, OrderPatchRequest, OrderPostRequest},
+			orders::Status,
+			position::Position,
+		},
+		error::AlpacaResult,
+		get_client::AlpacaClient,
+	},
+	futures::{StreamExt, future::BoxFuture, stream::BoxStream},
+	uuid::Uuid,
+};
+
+pub trait Broker: Send + Sync {
+	fn account(&self) -> BoxFuture<'_, AlpacaResult<Account>>;
+
+	fn clock(&self) -> BoxFuture<'_, AlpacaResult<ClockDTO>>;
+
+	fn positions(&self) -> BoxFuture<'_, AlpacaResult<Vec<Position>>>;
+
+	/// The open position of `symbol`, [`AlpacaError::NotFound`](crate::error::AlpacaError::NotFound)
+	/// without one.
+	fn position<'a>(&'a self, symbol: &'a str) -> BoxFuture<'a, AlpacaResult<Position>>;
+
+	fn orders(&self, status: Status) -> BoxFuture<'_, AlpacaResult<Vec<OrderDTO>>>;
+
+	fn order(&self, id: Uuid) -> BoxFuture<'_, AlpacaResult<OrderDTO>>;
+
+	fn submit_order<'a>(&'a self, order: &'a OrderPostRequest) -> BoxFuture<'a, AlpacaResult<OrderDTO>>;
+
+	fn replace_order<'a>(&'a self, id: Uuid, patch: &'a OrderPatchRequest) -> BoxFuture<'a, AlpacaResult<OrderDTO>>;
+
+	fn cancel_order(&self, id: Uuid) -> BoxFuture<'_, AlpacaResult<()>>;
+
+	fn cancel_all_orders(&self) -> BoxFuture<'_, AlpacaResult<Vec<CancelStatus>>>;
+
+	/// Updates of the orders from now on.
+	fn order_updates(&self) -> BoxStream<'static, AlpacaResult<OrderUpdate>>;
+}
+
+impl Broker for AlpacaClient {
+	fn account(&self) -> BoxFuture<'_, AlpacaResult<Account>> {
+		Box::pin(AlpacaClient::account(self))
+	}
+
+	fn clock(&self) -> BoxFuture<'_, AlpacaResult<ClockDTO>> {
+		Box::pin(AlpacaClient::clock(self))
+	}
+
+	fn positions(&self) -> BoxFuture<'_, AlpacaResult<Vec<Position>>> {
+		Box::pin(AlpacaClient::positions(self))
+	}
+
+	fn position<'a>(&'a self, symbol: &'a str) -> BoxFuture<'a, AlpacaResult<Position>> {
+		Box::pin(AlpacaClient::position(self, symbol))
+	}
+
+	fn orders(&self, status: Status) -> BoxFuture<'_, AlpacaResult<Vec<OrderDTO>>> {
+		Box::pin(AlpacaClient::orders(self).status(status).call())
+	}
+
+	fn order(&self, id: Uuid) -> BoxFuture<'_, AlpacaResult<OrderDTO>> {
+		Box::pin(AlpacaClient::order(self, id))
+	}
+
+	fn submit_order<'a>(&'a self, order: &'a OrderPostRequest) -> BoxFuture<'a, AlpacaResult<OrderDTO>> {
+		Box::pin(AlpacaClient::submit_order(self, order))
+	}
+
+	fn replace_order<'a>(&'a self, id: Uuid, patch: &'a OrderPatchRequest) -> BoxFuture<'a, AlpacaResult<OrderDTO>> {
+		Box::pin(AlpacaClient::replace_order(self, id, patch))
+	}
+
+	fn cancel_order(&self, id: Uuid) -> BoxFuture<'_, AlpacaResult<()>> {
+		Box::pin(AlpacaClient::cancel_order(self, id))
+	}
+
+	fn cancel_all_orders(&self) -> BoxFuture<'_, AlpacaResult<Vec<CancelStatus>>> {
+		Box::pin(AlpacaClient::cancel_all_orders(self))
+	}
+
+	/// Connects to the `trade_updates` stream, must be called within a Tokio
+	/// runtime.
+	fn order_updates(&self) -> BoxStream<'static, AlpacaResult<OrderUpdate>> {
+		AlpacaClient::order_updates(self).connect().boxed()
+	}
+}
@@ -0,0 +1,749 @@
+use {
+	super::{Broker, fill_model::FillModel},
+	crate::{
+		api::{
+			account::{self, Account},
+			assets::{Class as AssetClass, Exchange},
+			clock::ClockDTO,
+			enums::{
+				amount::Amount,
+				order_status::OrderStatus,
+				order_type::Type,
+				side::{SideBS, SideLS},
+				time_in_force::TimeInForce,
+				updates::OrderUpdate,
+			},
+			order::{CancelStatus, Class, OrderDTO, OrderPatchRequest, OrderPostRequest, Status},
+			orders,
+			position::Position,
+		},
+		data::bars::bars_dtos::NewBar,
+		error::{AlpacaError, AlpacaResult},
+	},
+	bigdecimal::{BigDecimal, RoundingMode, Zero},
+	chrono::{DateTime, NaiveDate, Utc},
+	futures::{
+		StreamExt,
+		future::BoxFuture,
+		stream::{self, BoxStream},
+	},
+	std::{
+		collections::{BTreeMap, HashMap, HashSet},
+		str::FromStr,
+	},
+	tokio::sync::{
+		Mutex,
+		mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel},
+	},
+	uuid::Uuid,
+};
+
+/// The decimals of quantities bought for a notional amount.
+const QUANTITY_SCALE: i64 = 9;
+/// The decimals kept by divisions, which would otherwise keep a hundred.
+const DIVISION_SCALE: i64 = 10;
+
+/// An in-memory broker filling orders against the bars it is given, to test
+/// order logic without network access.
+///
+/// Orders open when [`on_bar`](Self::on_bar) is called are checked against
+/// the bar of their symbol and fill completely:
+/// - market orders at the open,
+/// - limit orders at the open or the limit, whichever is better, once the bar reaches the limit,
+/// - stop orders at the open or the stop, whichever is worse, once the bar reaches the stop,
+/// - stop limit orders like limit orders once the stop is reached.
+///
+/// The fill model then adds slippage and commissions, and fills the cash or
+/// position can't cover are rejected. Immediate-or-cancel
+/// and fill-or-kill orders missing their first bar are canceled. Day orders
+/// trade in the day of their first bar, and expire without being checked on
+/// the first bar of their symbol on a later day.
+///
+/// Time is the time of the last bar, and the simulated market is always
+/// open. Trailing stops, multi-leg orders and orders at the open or close
+/// are not supported.
+#[derive(Debug)]
+pub struct SimulatedBroker {
+	state: Mutex<State>,
+	/// Order update subscribers, registered without waiting for the state.
+	subscribe: UnboundedSender<UnboundedSender<OrderUpdate>>,
+}
+
+#[bon::bon]
+impl SimulatedBroker {
+	#[builder]
+	pub fn new(
+		#[builder(into)] cash: BigDecimal,
+		/// The time before the first bar.
+		#[builder(default = Utc::now())]
+		now: DateTime<Utc>,
+		#[builder(default = true)] shorting_enabled: bool,
+		/// The exchange reported for every position.
+		#[builder(default = Exchange::Nasdaq)]
+		exchange: Exchange,
+		#[builder(default)] fill_model: FillModel,
+	) -> Self {
+		let (subscribe, subscriptions) = unbounded_channel();
+		let state = State {
+			account_id: Uuid::new_v4(),
+			created_at: now,
+			now,
+			last_equity: cash.clone(),
+			cash,
+			shorting_enabled,
+			exchange,
+			fill_model,
+			assets: HashMap::new(),
+			quotes: HashMap::new(),
+			holdings: BTreeMap::new(),
+			orders: Vec::new(),
+			triggered: HashSet::new(),
+			sessions: HashMap::new(),
+			subscriptions,
+			subscribers: Vec::new(),
+		};
+		Self { state: Mutex::new(state), subscribe }
+	}
+}
+
+impl SimulatedBroker {
+	/// Advance to `bar` of `symbol`, filling, canceling and expiring the open
+	/// orders it concerns. Bars must come in time order. Returns the updates,
+	/// which are also sent to the [`order_updates`](Broker::order_updates)
+	/// streams.
+	pub async fn on_bar(&self, symbol: &str, bar: &NewBar) -> Vec<OrderUpdate> {
+		let Some(prices) = BarPrices::new(bar) else {
+			return Vec::new();
+		};
+		let mut state = self.state.lock().await;
+		state.advance(bar.time);
+		let mut updates = Vec::new();
+		for index in 0..state.orders.len() {
+			let order = &state.orders[index];
+			if order.symbol != symbol || order.status.is_terminal() {
+				continue;
+			}
+			let (id, time_in_force) = (order.id, order.time_in_force);
+			let session = *state.sessions.entry(id).or_insert_with(|| bar.time.date_naive());
+			if time_in_force == TimeInForce::Day && session < bar.time.date_naive() {
+				updates.push(state.close(index, Status::Expired, OrderStatus::Expired, bar.time));
+				continue;
+			}
+			updates.extend(state.process(index, &prices, bar.time));
+		}
+		state.quote(symbol, prices.close, bar.time);
+		updates
+	}
+
+	async fn submit(&self, request: &OrderPostRequest) -> AlpacaResult<OrderDTO> {
+		request.validate()?;
+		check_supported(request.class, request.type_, request.time_in_force)?;
+		let mut state = self.state.lock().await;
+		let now = state.now;
+		let order = OrderDTO {
+			id: Uuid::new_v4(),
+			client_order_id: request.client_order_id.clone().unwrap_or_else(|| Uuid::new_v4().to_string()),
+			status: Status::New,
+			created_at: now,
+			updated_at: Some(now),
+			submitted_at: Some(now),
+			filled_at: None,
+			expired_at: None,
+			canceled_at: None,
+			asset_class: asset_class(&request.symbol),
+			asset_id: state.asset_id(&request.symbol),
+			symbol: request.symbol.clone(),
+			amount: request.amount.clone(),
+			filled_quantity: BigDecimal::zero(),
+			type_: request.type_,
+			class: request.class,
+			side: request.side,
+			time_in_force: request.time_in_force,
+			limit_price: request.limit_price.clone(),
+			stop_price: request.stop_price.clone(),
+			trail_price: None,
+			trail_percent: None,
+			average_fill_price: None,
+			extended_hours: request.extended_hours,
+			legs: None,
+		};
+		state.orders.push(order.clone());
+		state.emit(OrderStatus::New, &order);
+		Ok(order)
+	}
+
+	async fn replace(&self, id: Uuid, patch: &OrderPatchRequest) -> AlpacaResult<OrderDTO> {
+		patch.validate()?;
+		if patch.trail.is_some() {
+			return Err(unprocessable("trailing stops are not supported"));
+		}
+		let mut state = self.state.lock().await;
+		let index = state.open_order(id)?;
+		let old = &state.orders[index];
+		if patch.limit_price.is_some() && old.limit_price.is_none() || patch.stop_price.is_some() && old.stop_price.is_none() {
+			return Err(unprocessable("the order has no such price to replace"));
+		}
+		let now = state.now;
+		let mut order = OrderDTO {
+			id: Uuid::new_v4(),
+			client_order_id: patch.client_order_id.clone().unwrap_or_else(|| Uuid::new_v4().to_string()),
+			created_at: now,
+			updated_at: Some(now),
+			submitted_at: Some(now),
+			..old.clone()
+		};
+		if let Some(quantity) = &patch.quantity {
+			order.amount = Amount::quantity(quantity.clone());
+		}
+		order.time_in_force = patch.time_in_force.unwrap_or(order.time_in_force);
+		order.limit_price = patch.limit_price.clone().or(order.limit_price);
+		order.stop_price = patch.stop_price.clone().or(order.stop_price);
+		check_supported(order.class, order.type_, order.time_in_force)?;
+		state.close(index, Status::Replaced, OrderStatus::Replaced, now);
+		state.orders.push(order.clone());
+		state.emit(OrderStatus::New, &order);
+		Ok(order)
+	}
+
+	async fn cancel(&self, id: Uuid) -> AlpacaResult<()> {
+		let mut state = self.state.lock().await;
+		let index = state.open_order(id)?;
+		let now = state.now;
+		state.close(index, Status::Canceled, OrderStatus::Canceled, now);
+		Ok(())
+	}
+
+	async fn cancel_all(&self) -> Vec<CancelStatus> {
+		let mut state = self.state.lock().await;
+		let now = state.now;
+		(0..state.orders.len())
+			.filter(|&index| !state.orders[index].status.is_terminal())
+			.collect::<Vec<_>>()
+			.into_iter()
+			.map(|index| CancelStatus { id: state.close(index, Status::Canceled, OrderStatus::Canceled, now).order.id, status: 200 })
+			.collect()
+	}
+}
+
+impl Broker for SimulatedBroker {
+	fn account(&self) -> BoxFuture<'_, AlpacaResult<Account>> {
+		Box::pin(async { Ok(self.state.lock().await.account()) })
+	}
+
+	fn clock(&self) -> BoxFuture<'_, AlpacaResult<ClockDTO>> {
+		Box::pin(async {
+			let now = self.state.lock().await.now;
+			Ok(ClockDTO { open: true, current: now, next_open: now, next_close: now })
+		})
+	}
+
+	fn positions(&self) -> BoxFuture<'_, AlpacaResult<Vec<Position>>> {
+		Box::pin(async {
+			let state = self.state.lock().await;
+			Ok(state.holdings.iter().map(|(symbol, holding)| state.position(symbol, holding)).collect())
+		})
+	}
+
+	fn position<'a>(&'a self, symbol: &'a str) -> BoxFuture<'a, AlpacaResult<Position>> {
+		Box::pin(async move {
+			let state = self.state.lock().await;
+			let holding = state.holdings.get(symbol).ok_or_else(|| AlpacaError::NotFound { message: "position does not exist".to_owned() })?;
+			Ok(state.position(symbol, holding))
+		})
+	}
+
+	/// Newest first.
+	fn orders(&self, status: orders::Status) -> BoxFuture<'_, AlpacaResult<Vec<OrderDTO>>> {
+		Box::pin(async move {
+			let state = self.state.lock().await;
+			let orders = state.orders.iter().rev().filter(|order| match status {
+				orders::Status::Open => !order.status.is_terminal(),
+				orders::Status::Closed => order.status.is_terminal(),
+				orders::Status::All => true,
+			});
+			Ok(orders.cloned().collect())
+		})
+	}
+
+	fn order(&self, id: Uuid) -> BoxFuture<'_, AlpacaResult<OrderDTO>> {
+		Box::pin(async move {
+			let state = self.state.lock().await;
+			state.orders.iter().find(|order| order.id == id).cloned().ok_or_else(order_not_found)
+		})
+	}
+
+	fn submit_order<'a>(&'a self, order: &'a OrderPostRequest) -> BoxFuture<'a, AlpacaResult<OrderDTO>> {
+		Box::pin(self.submit(order))
+	}
+
+	fn replace_order<'a>(&'a self, id: Uuid, patch: &'a OrderPatchRequest) -> BoxFuture<'a, AlpacaResult<OrderDTO>> {
+		Box::pin(self.replace(id, patch))
+	}
+
+	fn cancel_order(&self, id: Uuid) -> BoxFuture<'_, AlpacaResult<()>> {
+		Box::pin(self.cancel(id))
+	}
+
+	fn cancel_all_orders(&self) -> BoxFuture<'_, AlpacaResult<Vec<CancelStatus>>> {
+		Box::pin(async { Ok(self.cancel_all().await) })
+	}
+
+	/// Ends when the broker is dropped.
+	fn order_updates(&self) -> BoxStream<'static, AlpacaResult<OrderUpdate>> {
+		let (updates_tx, updates) = unbounded_channel();
+		let _ = self.subscribe.send(updates_tx);
+		stream::unfold(updates, |mut updates| async move { updates.recv().await.map(|update| (Ok(update), updates)) }).boxed()
+	}
+}
+
+#[derive(Debug)]
+struct State {
+	account_id: Uuid,
+	created_at: DateTime<Utc>,
+	now: DateTime<Utc>,
+	cash: BigDecimal,
+	/// The equity at the end of the previous day.
+	last_equity: BigDecimal,
+	shorting_enabled: bool,
+	exchange: Exchange,
+	fill_model: FillModel,
+	assets: HashMap<String, Uuid>,
+	quotes: HashMap<String, Quote>,
+	holdings: BTreeMap<String, Holding>,
+	/// Every order, oldest first.
+	orders: Vec<OrderDTO>,
+	/// Stop limit orders whose stop was reached.
+	triggered: HashSet<Uuid>,
+	/// The day of the first bar each open order was checked against.
+	sessions: HashMap<Uuid, NaiveDate>,
+	subscriptions: UnboundedReceiver<UnboundedSender<OrderUpdate>>,
+	subscribers: Vec<UnboundedSender<OrderUpdate>>,
+}
+
+#[derive(Debug)]
+struct Quote {
+	price: BigDecimal,
+	time: DateTime<Utc>,
+	/// The last price of the previous day.
+	last_day_price: Option<BigDecimal>,
+}
+
+#[derive(Debug)]
+struct Holding {
+	/// Negative for short positions.
+	quantity: BigDecimal,
+	average_entry_price: BigDecimal,
+}
+
+impl State {
+	/// Move the clock to `time`, keeping the equity of the day it leaves.
+	fn advance(&mut self, time: DateTime<Utc>) {
+		if time.date_naive() > self.now.date_naive() {
+			self.last_equity = self.equity();
+		}
+		self.now = self.now.max(time);
+	}
+
+	/// Check the open order at `index` against a bar of its symbol.
+	fn process(&mut self, index: usize, bar: &BarPrices, time: DateTime<Utc>) -> Option<OrderUpdate> {
+		let order = &self.orders[index];
+		let side = order.side;
+		let price = match (order.type_, &order.limit_price, &order.stop_price) {
+			(Type::Market, _, _) => Some(self.fill_model.slipped(side, &bar.open)),
+			(Type::Limit, Some(limit), _) => bar.limit(side, limit),
+			(Type::Stop, _, Some(stop)) => bar.stop(side, stop).map(|price| self.fill_model.slipped(side, &price)),
+			(Type::StopLimit, Some(limit), Some(stop)) => {
+				let trigger = if self.triggered.contains(&order.id) { Some(bar.open.clone()) } else { bar.stop(side, stop) };
+				let Some(trigger) = trigger else {
+					return self.unfilled(index, time);
+				};
+				self.triggered.insert(order.id);
+				BarPrices { open: trigger, ..bar.clone() }.limit(side, limit)
+			},
+			_ => None,
+		};
+		let Some(price) = price else {
+			return self.unfilled(index, time);
+		};
+		let order = &self.orders[index];
+		let quantity = match &order.amount {
+			Amount::Quantity { quantity } => quantity.clone(),
+			Amount::Notional { notional } => (notional / &price).with_scale_round(QUANTITY_SCALE, RoundingMode::Down),
+		};
+		let held = self.holdings.get(&order.symbol).map_or_else(BigDecimal::zero, |holding| holding.quantity.clone());
+		let commission = self.fill_model.commission(&quantity, &price);
+		let covered = match side {
+			SideBS::Buy => &quantity * &price + &commission <= self.cash,
+			SideBS::Sell => self.shorting_enabled || quantity <= held,
+		};
+		if !covered || quantity.is_zero() {
+			return Some(self.close(index, Status::Rejected, OrderStatus::Rejected, time));
+		}
+		let signed = match side {
+			SideBS::Buy => quantity.clone(),
+			SideBS::Sell => -quantity.clone(),
+		};
+		self.cash -= &signed * &price + commission;
+		let symbol = order.symbol.clone();
+		let holding = self.holdings.entry(symbol.clone()).or_insert_with(|| Holding { quantity: BigDecimal::zero(), average_entry_price: BigDecimal::zero() });
+		let total = &holding.quantity + &signed;
+		if holding.quantity.is_zero() || (holding.quantity > BigDecimal::zero()) == (signed > BigDecimal::zero()) {
+			holding.average_entry_price = divide(&(&holding.quantity * &holding.average_entry_price + &signed * &price), &total);
+		} else if !total.is_zero() && (total > BigDecimal::zero()) != (holding.quantity > BigDecimal::zero()) {
+			// Crossed from long to short or back, the rest is a new position.
+			holding.average_entry_price = price.clone();
+		}
+		holding.quantity = total;
+		if holding.quantity.is_zero() {
+			self.holdings.remove(&symbol);
+		}
+		let order = &mut self.orders[index];
+		order.filled_quantity = quantity;
+		order.average_fill_price = Some(price);
+		order.filled_at = Some(time);
+		Some(self.close(index, Status::Filled, OrderStatus::Filled, time))
+	}
+
+	/// Cancel the order at `index` when it had to fill at once.
+	fn unfilled(&mut self, index: usize, time: DateTime<Utc>) -> Option<OrderUpdate> {
+		matches!(self.orders[index].time_in_force, TimeInForce::ImmediateOrCancel | TimeInForce::FillOrKill)
+			.then(|| self.close(index, Status::Canceled, OrderStatus::Canceled, time))
+	}
+
+	fn close(&mut self, index: usize, status: Status, event: OrderStatus, time: DateTime<Utc>) -> OrderUpdate {
+		let order = &mut self.orders[index];
+		order.status = status;
+		order.updated_at = Some(time);
+		match status {
+			Status::Canceled => order.canceled_at = Some(time),
+			Status::Expired => order.expired_at = Some(time),
+			_ => {},
+		}
+		self.triggered.remove(&order.id);
+		self.sessions.remove(&order.id);
+		let order = order.clone();
+		self.emit(event, &order)
+	}
+
+	fn emit(&mut self, event: OrderStatus, order: &OrderDTO) -> OrderUpdate {
+		while let Ok(subscriber) = self.subscriptions.try_recv() {
+			self.subscribers.push(subscriber);
+		}
+		let update = OrderUpdate { event, order: order.clone() };
+		self.subscribers.retain(|subscriber| subscriber.send(update.clone()).is_ok());
+		update
+	}
+
+	fn quote(&mut self, symbol: &str, price: BigDecimal, time: DateTime<Utc>) {
+		let last_day_price = self.quotes.get(symbol).and_then(|quote| match quote.time.date_naive() < time.date_naive() {
+			true => Some(quote.price.clone()),
+			false => quote.last_day_price.clone(),
+		});
+		self.quotes.insert(symbol.to_owned(), Quote { price, time, last_day_price });
+	}
+
+	fn open_order(&self, id: Uuid) -> AlpacaResult<usize> {
+		let index = self.orders.iter().position(|order| order.id == id).ok_or_else(order_not_found)?;
+		match self.orders[index].status.is_terminal() {
+			true => Err(unprocessable("order is not open")),
+			false => Ok(index),
+		}
+	}
+
+	fn asset_id(&mut self, symbol: &str) -> Uuid {
+		*self.assets.entry(symbol.to_owned()).or_insert_with(Uuid::new_v4)
+	}
+
+	fn price(&self, symbol: &str, holding: &Holding) -> BigDecimal {
+		self.quotes.get(symbol).map_or_else(|| holding.average_entry_price.clone(), |quote| quote.price.clone())
+	}
+
+	/// The value of the long and of the short positions, the latter negative.
+	fn market_values(&self) -> (BigDecimal, BigDecimal) {
+		let (mut long, mut short) = (BigDecimal::zero(), BigDecimal::zero());
+		for (symbol, holding) in &self.holdings {
+			let value = &holding.quantity * self.price(symbol, holding);
+			match value > BigDecimal::zero() {
+				true => long += value,
+				false => short += value,
+			}
+		}
+		(long, short)
+	}
+
+	fn equity(&self) -> BigDecimal {
+		let (long, short) = self.market_values();
+		&self.cash + long + short
+	}
+
+	fn account(&self) -> Account {
+		let (long, short) = self.market_values();
+		let equity = &self.cash + &long + &short;
+		Account {
+			id: self.account_id,
+			status: account::Status::Active,
+			currency: "USD".to_owned(),
+			buying_power: (&self.cash + &short).max(BigDecimal::zero()),
+			cash: self.cash.clone(),
+			day_trader: false,
+			trading_suspended: false,
+			trading_blocked: false,
+			transfers_blocked: false,
+			account_blocked: false,
+			created_at: self.created_at,
+			shorting_enabled: self.shorting_enabled,
+			market_value_long: long,
+			market_value_short: short,
+			equity,
+			last_equity: self.last_equity.clone(),
+			multiplier: BigDecimal::from(1),
+			initial_margin: BigDecimal::zero(),
+			maintenance_margin: BigDecimal::zero(),
+			daytrade_count: 0,
+		}
+	}
+
+	fn position(&self, symbol: &str, holding: &Holding) -> Position {
+		let price = self.price(symbol, holding);
+		let market_value = &holding.quantity * &price;
+		let cost_basis = &holding.quantity * &holding.average_entry_price;
+		let gain = &market_value - &cost_basis;
+		let last_day_price = self.quotes.get(symbol).and_then(|quote| quote.last_day_price.clone());
+		let change = last_day_price.as_ref().filter(|last| !last.is_zero()).map(|last| divide(&(&price - last), last));
+		let side = if holding.quantity > BigDecimal::zero() { SideLS::Long } else { SideLS::Short };
+		let direction = BigDecimal::from(if side == SideLS::Long { 1 } else { -1 });
+		Position {
+			asset_id: self.assets.get(symbol).copied().unwrap_or_default(),
+			symbol: symbol.to_owned(),
+			exchange: self.exchange,
+			asset_class: asset_class(symbol),
+			average_entry_price: holding.average_entry_price.clone(),
+			quantity: holding.quantity.clone(),
+			quantity_available: holding.quantity.clone(),
+			side,
+			unrealized_gain_total_percent: (!cost_basis.is_zero()).then(|| divide(&gain, &cost_basis.abs())),
+			unrealized_gain_today: last_day_price.as_ref().map(|last| (&price - last) * &holding.quantity),
+			unrealized_gain_today_percent: change.as_ref().map(|change| change * direction),
+			market_value: Some(market_value),
+			cost_basis,
+			unrealized_gain_total: Some(gain),
+			current_price: Some(price),
+			last_day_price,
+			change_today: change,
+		}
+	}
+}
+
+/// The prices of a bar as decimals.
+#[derive(Debug, Clone)]
+struct BarPrices {
+	open: BigDecimal,
+	high: BigDecimal,
+	low: BigDecimal,
+	close: BigDecimal,
+}
+
+impl BarPrices {
+	/// `None` when a price is not finite.
+	fn new(bar: &NewBar) -> Option<Self> {
+		Some(Self { open: decimal(bar.open)?, high: decimal(bar.high)?, low: decimal(bar.low)?, close: decimal(bar.close)? })
+	}
+
+	/// The fill price of a limit order, if the bar reaches `limit`.
+	fn limit(&self, side: SideBS, limit: &BigDecimal) -> Option<BigDecimal> {
+		match side {
+			SideBS::Buy => (self.low <= *limit).then(|| self.open.clone().min(limit.clone())),
+			SideBS::Sell => (self.high >= *limit).then(|| self.open.clone().max(limit.clone())),
+		}
+	}
+
+	/// The fill price of a stop order, if the bar reaches `stop`.
+	fn stop(&self, side: SideBS, stop: &BigDecimal) -> Option<BigDecimal> {
+		match side {
+			SideBS::Buy => (self.high >= *stop).then(|| self.open.clone().max(stop.clone())),
+			SideBS::Sell => (self.low <= *stop).then(|| self.open.clone().min(stop.clone())),
+		}
+	}
+}
+
+fn divide(dividend: &BigDecimal, divisor: &BigDecimal) -> BigDecimal {
+	(dividend / divisor).with_scale_round(DIVISION_SCALE, RoundingMode::HalfEven)
+}
+
+/// Go through the shortest representation, converting the `f32` itself
+/// would keep its binary noise.
+fn decimal(value: f32) -> Option<BigDecimal> {
+	value.is_finite().then(|| BigDecimal::from_str(&value.to_string()).ok()).flatten()
+}
+
+/// Crypto pairs are written `BASE/QUOTE`.
+fn asset_class(symbol: &str) -> AssetClass {
+	match symbol.contains('/') {
+		true => AssetClass::Crypto,
+		false => AssetClass::UsEquity,
+	}
+}
+
+fn check_supported(class: Class, type_: Type, time_in_force: TimeInForce) -> AlpacaResult<()> {
+	if class != Class::Simple {
+		return Err(unprocessable("multi-leg orders are not supported"));
+	}
+	if type_ == Type::TrailingStop {
+		return Err(unprocessable("trailing stops are not supported"));
+	}
+	if matches!(time_in_force, TimeInForce::UntilMarketOpen | TimeInForce::UntilMarketClose) {
+		return Err(unprocessable("orders at the open or close are not supported"));
+	}
+	Ok(())
+}
+
+fn unprocessable(message: &str) -> AlpacaError {
+	AlpacaError::UnprocessableOrder { code: None, message: message.to_owned() }
+}
+
+fn order_not_found() -> AlpacaError {
+	AlpacaError::NotFound { message: "order not found".to_owned() }
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, chrono::TimeZone};
+
+	fn time(day: u32, minute: u32) -> DateTime<Utc> {
+		Utc.with_ymd_and_hms(2024, 1, day, 15, minute, 0).unwrap()
+	}
+
+	fn bar(time: DateTime<Utc>, open: f32, high: f32, low: f32, close: f32) -> NewBar {
+		NewBar { time, open, high, low, close, volume: 100, trade_count: 1, weighted_average: close }
+	}
+
+	fn number(value: &str) -> BigDecimal {
+		BigDecimal::from_str(value).unwrap()
+	}
+
+	fn broker(cash: i32) -> SimulatedBroker {
+		SimulatedBroker::builder().cash(cash).now(time(2, 0)).build()
+	}
+
+	fn market(symbol: &str, side: SideBS, quantity: i32) -> OrderPostRequest {
+		OrderPostRequest::builder().symbol(symbol).amount(Amount::quantity(quantity)).side(side).build()
+	}
+
+	fn limit(side: SideBS, quantity: i32, price: &str, time_in_force: TimeInForce) -> OrderPostRequest {
+		OrderPostRequest::builder()
+			.symbol("AAPL")
+			.amount(Amount::quantity(quantity))
+			.side(side)
+			.type_(Type::Limit)
+			.limit_price(number(price))
+			.time_in_force(time_in_force)
+			.build()
+	}
+
+	#[tokio::test]
+	async fn market_orders_fill_at_the_open() {
+		let broker = broker(10_000);
+		let order = broker.submit_order(&market("AAPL", SideBS::Buy, 10)).await.unwrap();
+		let updates = broker.on_bar("AAPL", &bar(time(2, 1), 100.0, 101.0, 99.0, 100.5)).await;
+		assert_eq!(updates.len(), 1);
+		assert_eq!(updates[0].event, OrderStatus::Filled);
+		assert_eq!(updates[0].order.id, order.id);
+		assert_eq!(updates[0].order.average_fill_price, Some(number("100")));
+		assert_eq!(updates[0].order.filled_quantity, number("10"));
+	}
+
+	#[tokio::test]
+	async fn limit_orders_fill_at_the_better_of_the_open_and_the_limit() {
+		let broker = broker(10_000);
+		broker.submit_order(&limit(SideBS::Buy, 1, "99.5", TimeInForce::UntilCanceled)).await.unwrap();
+		broker.submit_order(&limit(SideBS::Buy, 1, "102", TimeInForce::UntilCanceled)).await.unwrap();
+		broker.submit_order(&limit(SideBS::Buy, 1, "90", TimeInForce::UntilCanceled)).await.unwrap();
+		let updates = broker.on_bar("AAPL", &bar(time(2, 1), 100.0, 101.0, 99.0, 100.5)).await;
+		let prices = updates.iter().map(|update| update.order.average_fill_price.clone()).collect::<Vec<_>>();
+		assert_eq!(prices, [Some(number("99.5")), Some(number("100"))]);
+		assert_eq!(broker.orders(orders::Status::Open).await.unwrap().len(), 1);
+	}
+
+	#[tokio::test]
+	async fn day_orders_expire_before_filling_on_a_later_day() {
+		let broker = broker(10_000);
+		let order = broker.submit_order(&limit(SideBS::Buy, 1, "95", TimeInForce::Day)).await.unwrap();
+		assert!(broker.on_bar("AAPL", &bar(time(2, 1), 100.0, 101.0, 99.0, 100.5)).await.is_empty());
+		let updates = broker.on_bar("AAPL", &bar(time(3, 1), 94.0, 96.0, 90.0, 92.0)).await;
+		assert_eq!(updates.len(), 1);
+		assert_eq!(updates[0].event, OrderStatus::Expired);
+		let order = broker.order(order.id).await.unwrap();
+		assert_eq!(order.status, Status::Expired);
+		assert_eq!(order.filled_quantity, BigDecimal::zero());
+		assert!(broker.positions().await.unwrap().is_empty());
+	}
+
+	#[tokio::test]
+	async fn day_orders_submitted_after_the_last_bar_trade_the_next_day() {
+		let broker = broker(10_000);
+		broker.on_bar("AAPL", &bar(time(2, 1), 100.0, 101.0, 99.0, 100.5)).await;
+		broker.submit_order(&market("AAPL", SideBS::Buy, 1)).await.unwrap();
+		let updates = broker.on_bar("AAPL", &bar(time(3, 1), 102.0, 103.0, 101.0, 102.5)).await;
+		assert_eq!(updates[0].event, OrderStatus::Filled);
+	}
+
+	#[tokio::test]
+	async fn immediate_orders_missing_their_first_bar_are_canceled() {
+		let broker = broker(10_000);
+		broker.submit_order(&limit(SideBS::Buy, 1, "90", TimeInForce::ImmediateOrCancel)).await.unwrap();
+		let updates = broker.on_bar("AAPL", &bar(time(2, 1), 100.0, 101.0, 99.0, 100.5)).await;
+		assert_eq!(updates[0].event, OrderStatus::Canceled);
+		assert!(updates[0].order.canceled_at.is_some());
+	}
+
+	#[tokio::test]
+	async fn uncovered_orders_are_rejected() {
+		let broker = SimulatedBroker::builder().cash(1_000).now(time(2, 0)).shorting_enabled(false).build();
+		broker.submit_order(&market("AAPL", SideBS::Buy, 20)).await.unwrap();
+		broker.submit_order(&market("AAPL", SideBS::Sell, 1)).await.unwrap();
+		let updates = broker.on_bar("AAPL", &bar(time(2, 1), 100.0, 101.0, 99.0, 100.5)).await;
+		assert!(updates.iter().all(|update| update.event == OrderStatus::Rejected));
+		assert_eq!(broker.account().await.unwrap().cash, number("1000"));
+	}
+
+	#[tokio::test]
+	async fn unsupported_orders_are_refused() {
+		let broker = broker(10_000);
+		let order = OrderPostRequest { time_in_force: TimeInForce::UntilMarketOpen, ..market("AAPL", SideBS::Buy, 1) };
+		assert!(matches!(broker.submit_order(&order).await, Err(AlpacaError::UnprocessableOrder { .. })));
+	}
+
+	#[tokio::test]
+	async fn fills_move_cash_and_positions() {
+		let fill_model = FillModel::builder().commission_per_order(1).build();
+		let broker = SimulatedBroker::builder().cash(10_000).now(time(2, 0)).fill_model(fill_model).build();
+		broker.submit_order(&market("AAPL", SideBS::Buy, 10)).await.unwrap();
+		broker.on_bar("AAPL", &bar(time(2, 1), 100.0, 101.0, 99.0, 100.0)).await;
+		broker.submit_order(&market("AAPL", SideBS::Sell, 4)).await.unwrap();
+		broker.on_bar("AAPL", &bar(time(2, 2), 110.0, 111.0, 109.0, 110.0)).await;
+
+		let account = broker.account().await.unwrap();
+		assert_eq!(account.cash, number("9438"));
+		assert_eq!(account.market_value_long, number("660"));
+		assert_eq!(account.equity, number("10098"));
+		let position = broker.position("AAPL").await.unwrap();
+		assert_eq!(position.quantity, number("6"));
+		assert_eq!(position.average_entry_price, number("100"));
+		assert_eq!(position.unrealized_gain_total, Some(number("60")));
+		assert_eq!(position.side, SideLS::Long);
+	}
+
+	#[tokio::test]
+	async fn short_sales_open_short_positions() {
+		let broker = broker(10_000);
+		broker.submit_order(&market("AAPL", SideBS::Sell, 2)).await.unwrap();
+		broker.on_bar("AAPL", &bar(time(2, 1), 200.0, 200.0, 190.0, 190.0)).await;
+		let account = broker.account().await.unwrap();
+		assert_eq!(account.cash, number("10400"));
+		assert_eq!(account.market_value_short, number("-380"));
+		assert_eq!(account.buying_power, number("10020"));
+		let position = broker.position("AAPL").await.unwrap();
+		assert_eq!(position.side, SideLS::Short);
+		assert_eq!(position.unrealized_gain_total, Some(number("20")));
+	}
+}
@@ -1,12 +1,21 @@
 use {
-	super::bars_dtos::{BarsDTO, BarsMultiApiDTO, BarsMultiRequestDTO, BarsSingleRequestDTO},
-	crate::data::enums::{adjustment::Adjustment, feed::Feed, timeframe::TimeFrame},
+	super::bars_dtos::{BarsDTO, BarsMultiApiDTO, BarsMultiRequestDTO, BarsSingleRequestDTO, NewBar},
+	crate::{
+		data::{
+			enums::{adjustment::Adjustment, feed::Feed, timeframe::TimeFrame},
+			pagination::paginate,
+		},
+		error::AlpacaResult,
+		get_client::AlpacaClient,
+		routes::EDataRoute,
+	},
 	chrono::{DateTime, Utc},
+	futures::Stream,
 };
 
 #[bon::builder]
 pub async fn bars_request_single_builder(
-	client: reqwest::Client,
+	client: &AlpacaClient,
 	symbol: String,
 	limit: Option<usize>,
 	start: Option<DateTime<Utc>>,
@@ -15,8 +24,9 @@ pub async fn bars_request_single_builder(
 	page_token: Option<String>,
 	#[builder(default)] timeframe: TimeFrame,
 	#[builder(default)] feed: Feed,
-) -> Result<BarsDTO, reqwest::Error> {
+) -> AlpacaResult<BarsDTO> {
 	bars_request_single(
+		client,
 		symbol,
 		BarsSingleRequestDTO::builder()
 			.maybe_limit(limit)
@@ -27,18 +37,17 @@ pub async fn bars_request_single_builder(
 			.timeframe(timeframe)
 			.feed(feed)
 			.build(),
-		client,
 	)
 	.await
 }
 
-pub async fn bars_request_single(symbol: String, request: BarsSingleRequestDTO, client: reqwest::Client) -> Result<BarsDTO, reqwest::Error> {
-	client.get(format!("https://data.alpaca.markets/v2/stocks/{symbol}/bars")).query(&request).send().await?.json::<BarsDTO>().await
+pub async fn bars_request_single(client: &AlpacaClient, symbol: String, request: BarsSingleRequestDTO) -> AlpacaResult<BarsDTO> {
+	client.send(client.get(client.url(EDataRoute::Bars(symbol))).query(&request)).await
 }
 
 #[bon::builder]
 pub async fn bars_request_multi_builder(
-	client: reqwest::Client,
+	client: &AlpacaClient,
 	symbols: Vec<String>,
 	limit: Option<usize>,
 	start: Option<DateTime<Utc>>,
@@ -47,8 +56,9 @@ pub async fn bars_request_multi_builder(
 	page_token: Option<String>,
 	#[builder(default)] timeframe: TimeFrame,
 	#[builder(default)] feed: Feed,
-) -> Result<BarsMultiApiDTO, reqwest::Error> {
+) -> AlpacaResult<BarsMultiApiDTO> {
 	bars_request_multi(
+		client,
 		BarsMultiRequestDTO::builder()
 			.symbols(symbols)
 			.params(
@@ -63,11 +73,79 @@ pub async fn bars_request_multi_builder(
 					.build(),
 			)
 			.build(),
-		client,
 	)
 	.await
 }
 
-pub async fn bars_request_multi(request: BarsMultiRequestDTO, client: reqwest::Client) -> Result<BarsMultiApiDTO, reqwest::Error> {
-	client.get("https://data.alpaca.markets/v2/stocks/bars".to_string()).query(&request).send().await?.json::<BarsMultiApiDTO>().await
+pub async fn bars_request_multi(client: &AlpacaClient, request: BarsMultiRequestDTO) -> AlpacaResult<BarsMultiApiDTO> {
+	client.send(client.get(client.url(EDataRoute::BarsMulti)).query(&request)).await
+}
+
+/// Bars of `symbol` across all pages. `limit` is the page size, up to 10000,
+/// `max_items` caps the total.
+#[bon::builder]
+pub fn bars_stream_single(
+	client: AlpacaClient,
+	symbol: String,
+	limit: Option<usize>,
+	max_items: Option<usize>,
+	start: Option<DateTime<Utc>>,
+	end: Option<DateTime<Utc>>,
+	adjustment: Option<Adjustment>,
+	#[builder(default)] timeframe: TimeFrame,
+	#[builder(default)] feed: Feed,
+) -> impl Stream<Item = AlpacaResult<NewBar>> {
+	paginate(max_items, move |page_token| {
+		let client = client.clone();
+		let symbol = symbol.clone();
+		async move {
+			bars_request_single_builder()
+				.client(&client)
+				.symbol(symbol)
+				.maybe_limit(limit)
+				.maybe_start(start)
+				.maybe_end(end)
+				.maybe_adjustment(adjustment)
+				.maybe_page_token(page_token)
+				.timeframe(timeframe)
+				.feed(feed)
+				.call()
+				.await
+		}
+	})
+}
+
+/// Bars of `symbols` across all pages, by symbol then time. Collect them with
+/// [`group_by_symbol`](crate::data::pagination::group_by_symbol). `limit` is
+/// the page size, up to 10000, `max_items` caps the total.
+#[bon::builder]
+pub fn bars_stream_multi(
+	client: AlpacaClient,
+	symbols: Vec<String>,
+	limit: Option<usize>,
+	max_items: Option<usize>,
+	start: Option<DateTime<Utc>>,
+	end: Option<DateTime<Utc>>,
+	adjustment: Option<Adjustment>,
+	#[builder(default)] timeframe: TimeFrame,
+	#[builder(default)] feed: Feed,
+) -> impl Stream<Item = AlpacaResult<(String, NewBar)>> {
+	paginate(max_items, move |page_token| {
+		let client = client.clone();
+		let symbols = symbols.clone();
+		async move {
+			bars_request_multi_builder()
+				.client(&client)
+				.symbols(symbols)
+				.maybe_limit(limit)
+				.maybe_start(start)
+				.maybe_end(end)
+				.maybe_adjustment(adjustment)
+				.maybe_page_token(page_token)
+				.timeframe(timeframe)
+				.feed(feed)
+				.call()
+				.await
+		}
+	})
 }
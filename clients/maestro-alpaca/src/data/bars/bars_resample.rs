@@ -0,0 +1,163 @@
+use {
+	super::bars_dtos::NewBar,
+	crate::data::enums::timeframe::TimeFrame,
+	std::fmt::{Display, Formatter, Result as FmtResult},
+};
+
+/// Bars of `from` can't be merged into bars of `to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResampleError {
+	pub from: TimeFrame,
+	pub to: TimeFrame,
+}
+
+impl Display for ResampleError {
+	fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+		write!(fmt, "{} bars can't be resampled into {} bars", self.from, self.to)
+	}
+}
+
+impl std::error::Error for ResampleError {}
+
+/// Merges bars into bars of a longer time frame as they come, for live
+/// data. A bar is complete once a bar of the next one arrives.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resampler {
+	timeframe: TimeFrame,
+	current: Option<Partial>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Partial {
+	bar: NewBar,
+	/// The traded value, to weight the average price.
+	value: f64,
+}
+
+impl Resampler {
+	/// # Errors
+	///
+	/// Fails when `to` is not made of whole bars of `from`.
+	pub fn new(from: TimeFrame, to: TimeFrame) -> Result<Self, ResampleError> {
+		match to.is_multiple_of(from) {
+			true => Ok(Self { timeframe: to, current: None }),
+			false => Err(ResampleError { from, to }),
+		}
+	}
+
+	/// Add the next bar, returning the previous resampled bar when `bar`
+	/// starts a new one.
+	pub fn push(&mut self, bar: &NewBar) -> Option<NewBar> {
+		let start = self.timeframe.bar_start(bar.time);
+		let value = f64::from(bar.weighted_average) * f64::from(bar.volume);
+		match &mut self.current {
+			Some(partial) if partial.bar.time == start => {
+				let merged = &mut partial.bar;
+				merged.high = merged.high.max(bar.high);
+				merged.low = merged.low.min(bar.low);
+				merged.close = bar.close;
+				merged.volume = merged.volume.saturating_add(bar.volume);
+				merged.trade_count = merged.trade_count.saturating_add(bar.trade_count);
+				partial.value += value;
+				None
+			},
+			current => {
+				let next = Partial { bar: NewBar { time: start, ..bar.clone() }, value };
+				current.replace(next).map(Partial::finish)
+			},
+		}
+	}
+
+	/// The resampled bar so far, `None` before the first bar.
+	pub fn current(&self) -> Option<NewBar> {
+		self.current.clone().map(Partial::finish)
+	}
+
+	/// Take the last resampled bar, complete or not.
+	pub fn flush(&mut self) -> Option<NewBar> {
+		self.current.take().map(Partial::finish)
+	}
+}
+
+impl Partial {
+	#[allow(clippy::cast_possible_truncation)]
+	fn finish(self) -> NewBar {
+		let Self { mut bar, value } = self;
+		if bar.volume > 0 {
+			bar.weighted_average = (value / f64::from(bar.volume)) as f32;
+		}
+		bar
+	}
+}
+
+/// Merge bars of `from`, oldest first, into bars of `to`. Bars are labeled
+/// with their start in UTC, so a day runs from midnight to midnight UTC.
+///
+/// # Errors
+///
+/// Fails when `to` is not made of whole bars of `from`.
+pub fn resample(bars: &[NewBar], from: TimeFrame, to: TimeFrame) -> Result<Vec<NewBar>, ResampleError> {
+	let mut resampler = Resampler::new(from, to)?;
+	let mut resampled = bars.iter().filter_map(|bar| resampler.push(bar)).collect::<Vec<_>>();
+	resampled.extend(resampler.flush());
+	Ok(resampled)
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		chrono::{TimeDelta, TimeZone, Utc},
+	};
+
+	fn minutes(count: i32) -> Vec<NewBar> {
+		let start = Utc.with_ymd_and_hms(2024, 5, 6, 13, 30, 0).unwrap();
+		(0..count)
+			.map(|minute| {
+				#[allow(clippy::cast_precision_loss)]
+				let price = 100.0 + minute as f32;
+				NewBar {
+					time: start + TimeDelta::minutes(minute.into()),
+					open: price - 0.5,
+					high: price + 1.0,
+					low: price - 1.0,
+					close: price,
+					volume: 10,
+					trade_count: 2,
+					weighted_average: price,
+				}
+			})
+			.collect()
+	}
+
+	#[test]
+	fn minutes_merge_into_quarters() {
+		let bars = resample(&minutes(40), TimeFrame::Minute, TimeFrame::Minute15).unwrap();
+		let start = Utc.with_ymd_and_hms(2024, 5, 6, 13, 30, 0).unwrap();
+		assert_eq!(bars.len(), 3);
+		assert_eq!(bars[0], NewBar { time: start, open: 99.5, high: 115.0, low: 99.0, close: 114.0, volume: 150, trade_count: 30, weighted_average: 107.0 });
+		assert_eq!(bars[1].time, start + TimeDelta::minutes(15));
+		// The last quarter is partial.
+		assert_eq!((bars[2].time, bars[2].close, bars[2].volume), (start + TimeDelta::minutes(30), 139.0, 100));
+	}
+
+	#[test]
+	fn bars_only_merge_into_multiples() {
+		let error = resample(&minutes(1), TimeFrame::Minute15, TimeFrame::Minute5).unwrap_err();
+		assert_eq!(error, ResampleError { from: TimeFrame::Minute15, to: TimeFrame::Minute5 });
+		assert_eq!(error.to_string(), "15 Minutes bars can't be resampled into 5 Minutes bars");
+		assert!(Resampler::new(TimeFrame::Month2, TimeFrame::Month3).is_err());
+		assert!(Resampler::new(TimeFrame::Day, TimeFrame::Month3).is_ok());
+	}
+
+	#[test]
+	fn resamplers_hold_the_bar_in_progress() {
+		let mut resampler = Resampler::new(TimeFrame::Minute, TimeFrame::Hour).unwrap();
+		let completed = minutes(40).iter().filter_map(|bar| resampler.push(bar)).collect::<Vec<_>>();
+		// 13:30 to 13:59 fall in the 13:00 bar, 14:00 starts the next one.
+		assert_eq!(completed.len(), 1);
+		assert_eq!(resampler.current().map(|bar| bar.close), Some(139.0));
+		assert_eq!(resampler.flush().map(|bar| bar.volume), Some(100));
+		assert_eq!(resampler.current(), None);
+	}
+}
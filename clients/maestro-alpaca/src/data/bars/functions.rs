@@ -5,5 +5,6 @@ use {
 
 #[server]
 pub async fn get_alpaca_bars_from_server(symbol: String, search_params: BarsSingleRequestDTO) -> Result<BarsDTO, ServerFnError> {
-	Ok(super::bars_reqwest::bars_request_single(symbol, search_params, crate::server_ctx::alpaca_client_from_ctx().await?).await?)
+	let alpaca = crate::server_ctx::alpaca_from_ctx().await?;
+	Ok(super::bars_reqwest::bars_request_single(&alpaca, symbol, search_params).await?)
 }
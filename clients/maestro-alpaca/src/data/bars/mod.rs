@@ -1,4 +1,5 @@
 pub mod bars_dtos;
+pub mod bars_resample;
 
 #[cfg(feature = "server")]
 pub mod bars_reqwest;
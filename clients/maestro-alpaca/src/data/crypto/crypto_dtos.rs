@@ -0,0 +1,135 @@
+use {
+	crate::data::{enums::timeframe::TimeFrame, last_quotes::last_quotes_dtos::serialize_vec_to_csv},
+	chrono::{DateTime, Utc},
+	serde::{Deserialize, Serialize},
+	std::collections::HashMap,
+};
+
+/// The query of crypto quotes and trades. Symbols are pairs like `BTC/USD`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CryptoRequestDTO {
+	#[serde(serialize_with = "serialize_vec_to_csv")]
+	pub symbols: Vec<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub start: Option<DateTime<Utc>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub end: Option<DateTime<Utc>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub limit: Option<usize>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub page_token: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CryptoBarsRequestDTO {
+	#[serde(flatten)]
+	pub params: CryptoRequestDTO,
+	pub timeframe: TimeFrame,
+}
+
+/// Volumes are fractional, unlike the stock ones of
+/// [`NewBar`](crate::data::bars::bars_dtos::NewBar).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CryptoBar {
+	#[serde(rename = "t")]
+	pub time: DateTime<Utc>,
+	#[serde(rename = "o")]
+	pub open: f64,
+	#[serde(rename = "h")]
+	pub high: f64,
+	#[serde(rename = "l")]
+	pub low: f64,
+	#[serde(rename = "c")]
+	pub close: f64,
+	#[serde(rename = "v")]
+	pub volume: f64,
+	#[serde(rename = "n")]
+	pub trade_count: u64,
+	#[serde(rename = "vw")]
+	pub weighted_average: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CryptoQuote {
+	#[serde(rename = "t")]
+	pub time: DateTime<Utc>,
+	#[serde(rename = "ap")]
+	pub ask_price: f64,
+	#[serde(rename = "as")]
+	pub ask_size: f64,
+	#[serde(rename = "bp")]
+	pub bid_price: f64,
+	#[serde(rename = "bs")]
+	pub bid_size: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TakerSide {
+	#[serde(rename = "B")]
+	Buy,
+	#[serde(rename = "S")]
+	Sell,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CryptoTrade {
+	#[serde(rename = "t")]
+	pub time: DateTime<Utc>,
+	#[serde(rename = "p")]
+	pub price: f64,
+	#[serde(rename = "s")]
+	pub size: f64,
+	#[serde(rename = "i")]
+	pub id: u64,
+	#[serde(rename = "tks")]
+	pub taker_side: TakerSide,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OrderbookLevel {
+	#[serde(rename = "p")]
+	pub price: f64,
+	#[serde(rename = "s")]
+	pub size: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Orderbook {
+	#[serde(rename = "t")]
+	pub time: DateTime<Utc>,
+	/// Best first.
+	#[serde(rename = "b")]
+	pub bids: Vec<OrderbookLevel>,
+	/// Best first.
+	#[serde(rename = "a")]
+	pub asks: Vec<OrderbookLevel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CryptoOrderbooksRequestDTO {
+	#[serde(serialize_with = "serialize_vec_to_csv")]
+	pub symbols: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CryptoBarsDTO {
+	pub bars: HashMap<String, Vec<CryptoBar>>,
+	pub next_page_token: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CryptoQuotesDTO {
+	pub quotes: HashMap<String, Vec<CryptoQuote>>,
+	pub next_page_token: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CryptoTradesDTO {
+	pub trades: HashMap<String, Vec<CryptoTrade>>,
+	pub next_page_token: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CryptoOrderbooksDTO {
+	pub orderbooks: HashMap<String, Orderbook>,
+}
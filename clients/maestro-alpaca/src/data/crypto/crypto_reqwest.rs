@@ -0,0 +1,177 @@
+use {
+	super::crypto_dtos::{
+		CryptoBar, CryptoBarsDTO, CryptoBarsRequestDTO, CryptoOrderbooksDTO, CryptoOrderbooksRequestDTO, CryptoQuote, CryptoQuotesDTO, CryptoRequestDTO,
+		CryptoTrade, CryptoTradesDTO,
+	},
+	crate::{
+		alpaca_env::AlpacaUrls,
+		data::{
+			enums::{crypto_feed::CryptoFeed, timeframe::TimeFrame},
+			pagination::paginate,
+		},
+		error::AlpacaResult,
+		get_client::{RetryPolicy, decode},
+		routes::{EAlpacaRoute, EDataRoute},
+	},
+	chrono::{DateTime, Utc},
+	futures::Stream,
+	reqwest::Client,
+};
+
+/// Crypto data needs no credentials, so `client` defaults to a plain one.
+/// `retry` applies to rate limits like [`AlpacaClient::retry`](crate::get_client::AlpacaClient::retry).
+#[bon::builder]
+pub async fn crypto_bars_request(
+	urls: AlpacaUrls,
+	#[builder(default)] feed: CryptoFeed,
+	symbols: Vec<String>,
+	#[builder(default)] timeframe: TimeFrame,
+	start: Option<DateTime<Utc>>,
+	end: Option<DateTime<Utc>>,
+	limit: Option<usize>,
+	page_token: Option<String>,
+	#[builder(default = Client::new())] client: reqwest::Client,
+	#[builder(default)] retry: RetryPolicy,
+) -> AlpacaResult<CryptoBarsDTO> {
+	let request = CryptoBarsRequestDTO { params: CryptoRequestDTO { symbols, start, end, limit, page_token }, timeframe };
+	decode(retry.send(client.get(EAlpacaRoute::Data(EDataRoute::CryptoBars(feed)).url_path(&urls)).query(&request)).await?).await
+}
+
+/// Bars of `symbols` across all pages, symbol by symbol. `limit` is the page
+/// size, up to 10000, `max_items` caps the total.
+#[bon::builder]
+pub fn crypto_bars_stream(
+	urls: AlpacaUrls,
+	#[builder(default)] feed: CryptoFeed,
+	symbols: Vec<String>,
+	#[builder(default)] timeframe: TimeFrame,
+	start: Option<DateTime<Utc>>,
+	end: Option<DateTime<Utc>>,
+	limit: Option<usize>,
+	max_items: Option<usize>,
+	#[builder(default = Client::new())] client: reqwest::Client,
+	#[builder(default)] retry: RetryPolicy,
+) -> impl Stream<Item = AlpacaResult<(String, CryptoBar)>> {
+	paginate(max_items, move |page_token| {
+		crypto_bars_request()
+			.urls(urls.clone())
+			.feed(feed)
+			.symbols(symbols.clone())
+			.timeframe(timeframe)
+			.maybe_start(start)
+			.maybe_end(end)
+			.maybe_limit(limit)
+			.maybe_page_token(page_token)
+			.client(client.clone())
+			.retry(retry)
+			.call()
+	})
+}
+
+/// See [`crypto_bars_request`] for `client` and `retry`.
+#[bon::builder]
+pub async fn crypto_quotes_request(
+	urls: AlpacaUrls,
+	#[builder(default)] feed: CryptoFeed,
+	symbols: Vec<String>,
+	start: Option<DateTime<Utc>>,
+	end: Option<DateTime<Utc>>,
+	limit: Option<usize>,
+	page_token: Option<String>,
+	#[builder(default = Client::new())] client: reqwest::Client,
+	#[builder(default)] retry: RetryPolicy,
+) -> AlpacaResult<CryptoQuotesDTO> {
+	let request = CryptoRequestDTO { symbols, start, end, limit, page_token };
+	decode(retry.send(client.get(EAlpacaRoute::Data(EDataRoute::CryptoQuotes(feed)).url_path(&urls)).query(&request)).await?).await
+}
+
+/// Quotes of `symbols` across all pages, symbol by symbol. `limit` is the
+/// page size, up to 10000, `max_items` caps the total.
+#[bon::builder]
+pub fn crypto_quotes_stream(
+	urls: AlpacaUrls,
+	#[builder(default)] feed: CryptoFeed,
+	symbols: Vec<String>,
+	start: Option<DateTime<Utc>>,
+	end: Option<DateTime<Utc>>,
+	limit: Option<usize>,
+	max_items: Option<usize>,
+	#[builder(default = Client::new())] client: reqwest::Client,
+	#[builder(default)] retry: RetryPolicy,
+) -> impl Stream<Item = AlpacaResult<(String, CryptoQuote)>> {
+	paginate(max_items, move |page_token| {
+		crypto_quotes_request()
+			.urls(urls.clone())
+			.feed(feed)
+			.symbols(symbols.clone())
+			.maybe_start(start)
+			.maybe_end(end)
+			.maybe_limit(limit)
+			.maybe_page_token(page_token)
+			.client(client.clone())
+			.retry(retry)
+			.call()
+	})
+}
+
+/// See [`crypto_bars_request`] for `client` and `retry`.
+#[bon::builder]
+pub async fn crypto_trades_request(
+	urls: AlpacaUrls,
+	#[builder(default)] feed: CryptoFeed,
+	symbols: Vec<String>,
+	start: Option<DateTime<Utc>>,
+	end: Option<DateTime<Utc>>,
+	limit: Option<usize>,
+	page_token: Option<String>,
+	#[builder(default = Client::new())] client: reqwest::Client,
+	#[builder(default)] retry: RetryPolicy,
+) -> AlpacaResult<CryptoTradesDTO> {
+	let request = CryptoRequestDTO { symbols, start, end, limit, page_token };
+	decode(retry.send(client.get(EAlpacaRoute::Data(EDataRoute::CryptoTrades(feed)).url_path(&urls)).query(&request)).await?).await
+}
+
+/// Trades of `symbols` across all pages, symbol by symbol. `limit` is the
+/// page size, up to 10000, `max_items` caps the total.
+#[bon::builder]
+pub fn crypto_trades_stream(
+	urls: AlpacaUrls,
+	#[builder(default)] feed: CryptoFeed,
+	symbols: Vec<String>,
+	start: Option<DateTime<Utc>>,
+	end: Option<DateTime<Utc>>,
+	limit: Option<usize>,
+	max_items: Option<usize>,
+	#[builder(default = Client::new())] client: reqwest::Client,
+	#[builder(default)] retry: RetryPolicy,
+) -> impl Stream<Item = AlpacaResult<(String, CryptoTrade)>> {
+	paginate(max_items, move |page_token| {
+		crypto_trades_request()
+			.urls(urls.clone())
+			.feed(feed)
+			.symbols(symbols.clone())
+			.maybe_start(start)
+			.maybe_end(end)
+			.maybe_limit(limit)
+			.maybe_page_token(page_token)
+			.client(client.clone())
+			.retry(retry)
+			.call()
+	})
+}
+
+/// The latest order book of each of `symbols`. See [`crypto_bars_request`]
+/// for `client` and `retry`.
+#[bon::builder]
+pub async fn crypto_latest_orderbooks_request(
+	urls: AlpacaUrls,
+	#[builder(default)] feed: CryptoFeed,
+	symbols: Vec<String>,
+	#[builder(default = Client::new())] client: reqwest::Client,
+	#[builder(default)] retry: RetryPolicy,
+) -> AlpacaResult<CryptoOrderbooksDTO> {
+	decode(
+		retry.send(client.get(EAlpacaRoute::Data(EDataRoute::CryptoLatestOrderbooks(feed)).url_path(&urls)).query(&CryptoOrderbooksRequestDTO { symbols })).await?,
+	)
+	.await
+}
@@ -0,0 +1,4 @@
+pub mod crypto_dtos;
+
+#[cfg(feature = "server")]
+pub mod crypto_reqwest;
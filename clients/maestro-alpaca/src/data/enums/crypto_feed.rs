@@ -0,0 +1,33 @@
+use {
+	serde::{Deserialize, Serialize},
+	strum_macros::{Display, EnumIter, EnumString},
+};
+
+/// The venue of crypto market data, part of the route rather than a query
+/// parameter.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter, Display, EnumString)]
+pub enum CryptoFeed {
+	/// Alpaca's own crypto exchange.
+	#[serde(rename = "us")]
+	#[default]
+	#[strum(to_string = "Alpaca")]
+	Us,
+	/// Kraken in the US.
+	#[serde(rename = "us-1")]
+	#[strum(to_string = "Kraken US")]
+	Us1,
+	/// Kraken in the EU.
+	#[serde(rename = "eu-1")]
+	#[strum(to_string = "Kraken EU")]
+	Eu1,
+}
+
+impl CryptoFeed {
+	pub fn path(self) -> &'static str {
+		match self {
+			Self::Us => "us",
+			Self::Us1 => "us-1",
+			Self::Eu1 => "eu-1",
+		}
+	}
+}
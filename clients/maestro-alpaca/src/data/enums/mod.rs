@@ -1,3 +1,5 @@
 pub mod adjustment;
+pub mod crypto_feed;
 pub mod feed;
+pub mod options_feed;
 pub mod timeframe;
@@ -0,0 +1,19 @@
+use {
+	serde::{Deserialize, Serialize},
+	strum_macros::{Display, EnumIter, EnumString},
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter, Display, EnumString)]
+pub enum OptionsFeed {
+	/// Use the Options Price Reporting Authority (OPRA) as the data source.
+	///
+	/// This feed is only usable with the unlimited market data plan.
+	#[serde(rename = "opra")]
+	#[strum(to_string = "OPRA")]
+	Opra,
+	/// Use a free, delayed and modified version of OPRA as the data source.
+	#[serde(rename = "indicative")]
+	#[default]
+	#[strum(to_string = "Indicative")]
+	Indicative,
+}
@@ -1,4 +1,5 @@
 use {
+	chrono::{DateTime, Datelike, NaiveDate, NaiveTime, TimeDelta, Utc},
 	serde::{Deserialize, Serialize},
 	strum_macros::{Display, EnumIter, EnumString},
 };
@@ -14,7 +15,11 @@ pub enum TimeFrameOne {
 	Day,
 }
 
+/// Stored as the `alpaca_timeframe` Postgres enum, with snake case variant
+/// names.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter, Display, EnumString)]
+#[cfg_attr(feature = "server", derive(diesel_derive_enum::DbEnum))]
+#[cfg_attr(feature = "server", ExistingTypePath = "crate::db::schema::sql_types::AlpacaTimeframe")]
 pub enum TimeFrame {
 	#[serde(rename = "1Min")]
 	#[strum(to_string = "1 Minute")]
@@ -63,3 +68,56 @@ pub enum TimeFrame {
 	#[strum(to_string = "12 Months")]
 	Month12,
 }
+
+impl TimeFrame {
+	/// The length of a bar, `None` for the calendar months.
+	pub fn duration(self) -> Option<TimeDelta> {
+		match self {
+			Self::Minute => Some(TimeDelta::minutes(1)),
+			Self::Minute5 => Some(TimeDelta::minutes(5)),
+			Self::Minute15 => Some(TimeDelta::minutes(15)),
+			Self::Minute30 => Some(TimeDelta::minutes(30)),
+			Self::Hour => Some(TimeDelta::hours(1)),
+			Self::Hour2 => Some(TimeDelta::hours(2)),
+			Self::Hour6 => Some(TimeDelta::hours(6)),
+			Self::Hour12 => Some(TimeDelta::hours(12)),
+			Self::Day => Some(TimeDelta::days(1)),
+			_ => None,
+		}
+	}
+
+	/// The months of a bar, `None` below a month.
+	pub fn months(self) -> Option<u32> {
+		match self {
+			Self::Month => Some(1),
+			Self::Month2 => Some(2),
+			Self::Month3 => Some(3),
+			Self::Month4 => Some(4),
+			Self::Month6 => Some(6),
+			Self::Month12 => Some(12),
+			_ => None,
+		}
+	}
+
+	/// Whether bars of `self` are made of whole bars of `other`.
+	pub fn is_multiple_of(self, other: Self) -> bool {
+		match (self.duration(), other.duration(), self.months(), other.months()) {
+			(Some(this), Some(other), ..) => this.num_seconds() % other.num_seconds() == 0,
+			(_, Some(_), Some(_), _) => true,
+			(.., Some(this), Some(other)) => this % other == 0,
+			_ => false,
+		}
+	}
+
+	/// The start of the bar holding `time`, in UTC. Months are counted from
+	/// January, so 3 month bars are quarters.
+	pub fn bar_start(self, time: DateTime<Utc>) -> DateTime<Utc> {
+		if let Some(duration) = self.duration() {
+			let seconds = duration.num_seconds();
+			return DateTime::from_timestamp(time.timestamp().div_euclid(seconds) * seconds, 0).unwrap_or(time);
+		}
+		let months = self.months().unwrap_or(1);
+		let month = (time.month0() / months) * months + 1;
+		NaiveDate::from_ymd_opt(time.year(), month, 1).map_or(time, |date| date.and_time(NaiveTime::MIN).and_utc())
+	}
+}
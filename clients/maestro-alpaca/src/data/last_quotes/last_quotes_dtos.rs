@@ -22,7 +22,23 @@ where
 	vec.join(",").serialize(serializer)
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Same as [`serialize_vec_to_csv`] for enums serialized as strings.
+pub fn serialize_enums_to_csv<T, S>(vec: &[T], serializer: S) -> Result<S::Ok, S::Error>
+where
+	T: Serialize,
+	S: serde::Serializer,
+{
+	let values = vec
+		.iter()
+		.map(|value| match serde_json::to_value(value) {
+			Ok(serde_json::Value::String(value)) => Ok(value),
+			_ => Err(serde::ser::Error::custom("expected a value serialized as a string")),
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+	serialize_vec_to_csv(&values, serializer)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct QuoteDTO {
 	#[serde(rename = "t")]
 	pub time: DateTime<Utc>,
@@ -1,20 +1,20 @@
 use {
 	super::last_quotes_dtos::{LatestQuotesRequestDTO, LatestQuotesResponseDTO, QuoteResponseDTO},
-	crate::data::enums::feed::Feed,
+	crate::{data::enums::feed::Feed, error::AlpacaResult, get_client::AlpacaClient, routes::EDataRoute},
 };
 
 #[bon::builder]
 pub async fn latest_quotes_request_builder(
-	client: reqwest::Client,
+	client: &AlpacaClient,
 	symbols: Vec<String>,
 	#[builder(default)] feed: Feed,
 	#[builder(default)] currency: String,
-) -> Result<Vec<QuoteResponseDTO>, reqwest::Error> {
-	latest_quotes_request(LatestQuotesRequestDTO::builder().symbols(symbols).feed(feed).currency(currency).build(), client).await
+) -> AlpacaResult<Vec<QuoteResponseDTO>> {
+	latest_quotes_request(client, LatestQuotesRequestDTO::builder().symbols(symbols).feed(feed).currency(currency).build()).await
 }
 
-pub async fn latest_quotes_request(request: LatestQuotesRequestDTO, client: reqwest::Client) -> Result<Vec<QuoteResponseDTO>, reqwest::Error> {
-	let response = client.get("https://data.alpaca.markets/v2/stocks/quotes/latest").query(&request).send().await?.json::<LatestQuotesResponseDTO>().await?;
+pub async fn latest_quotes_request(client: &AlpacaClient, request: LatestQuotesRequestDTO) -> AlpacaResult<Vec<QuoteResponseDTO>> {
+	let response: LatestQuotesResponseDTO = client.send(client.get(client.url(EDataRoute::LatestQuotes)).query(&request)).await?;
 
 	Ok(response.into())
 }
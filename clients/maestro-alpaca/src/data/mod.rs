@@ -1,4 +1,11 @@
 pub mod bars;
+pub mod crypto;
 pub mod last_quotes;
+pub mod options;
+#[cfg(feature = "server")]
+pub mod pagination;
+pub mod quotes;
+pub mod stream;
+pub mod trades;
 
 pub mod enums;
@@ -0,0 +1,4 @@
+pub mod options_dtos;
+
+#[cfg(feature = "server")]
+pub mod options_reqwest;
@@ -0,0 +1,118 @@
+use {
+	crate::data::{bars::bars_dtos::NewBar, enums::options_feed::OptionsFeed, last_quotes::last_quotes_dtos::serialize_vec_to_csv},
+	chrono::{DateTime, NaiveDate, Utc},
+	serde::{Deserialize, Serialize},
+	std::collections::HashMap,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContractType {
+	Call,
+	Put,
+}
+
+/// The filters of the contracts of an underlying symbol.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OptionChainRequestDTO {
+	pub feed: OptionsFeed,
+	#[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+	pub contract_type: Option<ContractType>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub strike_price_gte: Option<f64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub strike_price_lte: Option<f64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub expiration_date: Option<NaiveDate>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub expiration_date_gte: Option<NaiveDate>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub expiration_date_lte: Option<NaiveDate>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub root_symbol: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub updated_since: Option<DateTime<Utc>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub limit: Option<usize>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub page_token: Option<String>,
+}
+
+/// Symbols are contracts like `AAPL240119C00100000`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OptionSnapshotsRequestDTO {
+	#[serde(serialize_with = "serialize_vec_to_csv")]
+	pub symbols: Vec<String>,
+	pub feed: OptionsFeed,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub updated_since: Option<DateTime<Utc>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub limit: Option<usize>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub page_token: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptionTrade {
+	#[serde(rename = "t")]
+	pub time: DateTime<Utc>,
+	#[serde(rename = "x")]
+	pub exchange: String,
+	#[serde(rename = "p")]
+	pub price: f64,
+	#[serde(rename = "s")]
+	pub size: u64,
+	#[serde(rename = "c")]
+	pub condition: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptionQuote {
+	#[serde(rename = "t")]
+	pub time: DateTime<Utc>,
+	#[serde(rename = "ax")]
+	pub ask_exchange: String,
+	#[serde(rename = "ap")]
+	pub ask_price: f64,
+	#[serde(rename = "as")]
+	pub ask_size: u64,
+	#[serde(rename = "bx")]
+	pub bid_exchange: String,
+	#[serde(rename = "bp")]
+	pub bid_price: f64,
+	#[serde(rename = "bs")]
+	pub bid_size: u64,
+	#[serde(rename = "c")]
+	pub condition: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Greeks {
+	pub delta: f64,
+	pub gamma: f64,
+	pub rho: f64,
+	pub theta: f64,
+	pub vega: f64,
+}
+
+/// Greeks and implied volatility are missing for contracts that did not
+/// trade recently.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionSnapshot {
+	pub latest_trade: Option<OptionTrade>,
+	pub latest_quote: Option<OptionQuote>,
+	pub implied_volatility: Option<f64>,
+	pub greeks: Option<Greeks>,
+	pub minute_bar: Option<NewBar>,
+	pub daily_bar: Option<NewBar>,
+	pub prev_daily_bar: Option<NewBar>,
+}
+
+/// Snapshots by contract symbol, the response of both the chain and the
+/// snapshots endpoints.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptionSnapshotsDTO {
+	pub snapshots: HashMap<String, OptionSnapshot>,
+	pub next_page_token: Option<String>,
+}
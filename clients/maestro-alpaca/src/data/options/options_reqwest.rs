@@ -0,0 +1,103 @@
+use {
+	super::options_dtos::{ContractType, OptionChainRequestDTO, OptionSnapshot, OptionSnapshotsDTO, OptionSnapshotsRequestDTO},
+	crate::{
+		data::{enums::options_feed::OptionsFeed, pagination::paginate},
+		error::AlpacaResult,
+		get_client::AlpacaClient,
+		routes::EDataRoute,
+	},
+	chrono::{DateTime, NaiveDate, Utc},
+	futures::Stream,
+};
+
+/// Snapshots of the contracts of `underlying`. The options endpoints need the
+/// credentials of `client`.
+#[bon::builder]
+pub async fn option_chain_request(
+	client: &AlpacaClient,
+	underlying: String,
+	#[builder(default)] feed: OptionsFeed,
+	contract_type: Option<ContractType>,
+	strike_price_gte: Option<f64>,
+	strike_price_lte: Option<f64>,
+	expiration_date: Option<NaiveDate>,
+	expiration_date_gte: Option<NaiveDate>,
+	expiration_date_lte: Option<NaiveDate>,
+	root_symbol: Option<String>,
+	updated_since: Option<DateTime<Utc>>,
+	limit: Option<usize>,
+	page_token: Option<String>,
+) -> AlpacaResult<OptionSnapshotsDTO> {
+	let request = OptionChainRequestDTO {
+		feed,
+		contract_type,
+		strike_price_gte,
+		strike_price_lte,
+		expiration_date,
+		expiration_date_gte,
+		expiration_date_lte,
+		root_symbol,
+		updated_since,
+		limit,
+		page_token,
+	};
+	client.send(client.get(client.url(EDataRoute::OptionChain(underlying))).query(&request)).await
+}
+
+/// The chain of `underlying` across all pages, sorted by contract within a
+/// page. `limit` is the page size, up to 1000, `max_items` caps the total.
+#[bon::builder]
+pub fn option_chain_stream(
+	client: AlpacaClient,
+	underlying: String,
+	#[builder(default)] feed: OptionsFeed,
+	contract_type: Option<ContractType>,
+	strike_price_gte: Option<f64>,
+	strike_price_lte: Option<f64>,
+	expiration_date: Option<NaiveDate>,
+	expiration_date_gte: Option<NaiveDate>,
+	expiration_date_lte: Option<NaiveDate>,
+	root_symbol: Option<String>,
+	updated_since: Option<DateTime<Utc>>,
+	limit: Option<usize>,
+	max_items: Option<usize>,
+) -> impl Stream<Item = AlpacaResult<(String, OptionSnapshot)>> {
+	paginate(max_items, move |page_token| {
+		let client = client.clone();
+		let underlying = underlying.clone();
+		let root_symbol = root_symbol.clone();
+		async move {
+			option_chain_request()
+				.client(&client)
+				.underlying(underlying)
+				.feed(feed)
+				.maybe_contract_type(contract_type)
+				.maybe_strike_price_gte(strike_price_gte)
+				.maybe_strike_price_lte(strike_price_lte)
+				.maybe_expiration_date(expiration_date)
+				.maybe_expiration_date_gte(expiration_date_gte)
+				.maybe_expiration_date_lte(expiration_date_lte)
+				.maybe_root_symbol(root_symbol)
+				.maybe_updated_since(updated_since)
+				.maybe_limit(limit)
+				.maybe_page_token(page_token)
+				.call()
+				.await
+		}
+	})
+}
+
+/// Snapshots of the contracts in `symbols`. The options endpoints need the
+/// credentials of `client`.
+#[bon::builder]
+pub async fn option_snapshots_request(
+	client: &AlpacaClient,
+	symbols: Vec<String>,
+	#[builder(default)] feed: OptionsFeed,
+	updated_since: Option<DateTime<Utc>>,
+	limit: Option<usize>,
+	page_token: Option<String>,
+) -> AlpacaResult<OptionSnapshotsDTO> {
+	let request = OptionSnapshotsRequestDTO { symbols, feed, updated_since, limit, page_token };
+	client.send(client.get(client.url(EDataRoute::OptionSnapshots)).query(&request)).await
+}
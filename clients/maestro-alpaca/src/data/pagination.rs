@@ -0,0 +1,233 @@
+use {
+	crate::data::{
+		bars::bars_dtos::{BarsDTO, BarsMultiApiDTO, NewBar},
+		crypto::crypto_dtos::{CryptoBar, CryptoBarsDTO, CryptoQuote, CryptoQuotesDTO, CryptoTrade, CryptoTradesDTO},
+		last_quotes::last_quotes_dtos::QuoteDTO,
+		options::options_dtos::{OptionSnapshot, OptionSnapshotsDTO},
+		quotes::quotes_dtos::QuotesDTO,
+		trades::trades_dtos::{TradeDTO, TradesDTO},
+	},
+	futures::{Stream, StreamExt, TryStreamExt, stream},
+	std::{collections::HashMap, future::Future},
+};
+
+/// A response of a paginated endpoint.
+pub trait Page {
+	type Item;
+
+	/// The items, in order, and the token of the next page.
+	fn into_parts(self) -> (Vec<Self::Item>, Option<String>);
+}
+
+impl Page for BarsDTO {
+	type Item = NewBar;
+
+	fn into_parts(self) -> (Vec<Self::Item>, Option<String>) {
+		(self.bars, self.next_page_token)
+	}
+}
+
+impl Page for BarsMultiApiDTO {
+	type Item = (String, NewBar);
+
+	fn into_parts(self) -> (Vec<Self::Item>, Option<String>) {
+		(by_symbol(self.bars), self.next_page_token)
+	}
+}
+
+impl Page for QuotesDTO {
+	type Item = QuoteDTO;
+
+	fn into_parts(self) -> (Vec<Self::Item>, Option<String>) {
+		(self.quotes, self.next_page_token)
+	}
+}
+
+impl Page for TradesDTO {
+	type Item = TradeDTO;
+
+	fn into_parts(self) -> (Vec<Self::Item>, Option<String>) {
+		(self.trades, self.next_page_token)
+	}
+}
+
+impl Page for CryptoBarsDTO {
+	type Item = (String, CryptoBar);
+
+	fn into_parts(self) -> (Vec<Self::Item>, Option<String>) {
+		(by_symbol(self.bars), self.next_page_token)
+	}
+}
+
+impl Page for CryptoQuotesDTO {
+	type Item = (String, CryptoQuote);
+
+	fn into_parts(self) -> (Vec<Self::Item>, Option<String>) {
+		(by_symbol(self.quotes), self.next_page_token)
+	}
+}
+
+impl Page for CryptoTradesDTO {
+	type Item = (String, CryptoTrade);
+
+	fn into_parts(self) -> (Vec<Self::Item>, Option<String>) {
+		(by_symbol(self.trades), self.next_page_token)
+	}
+}
+
+/// Snapshots by contract symbol.
+impl Page for OptionSnapshotsDTO {
+	type Item = (String, OptionSnapshot);
+
+	fn into_parts(self) -> (Vec<Self::Item>, Option<String>) {
+		let mut snapshots = self.snapshots.into_iter().collect::<Vec<_>>();
+		snapshots.sort_by(|(a, _), (b, _)| a.cmp(b));
+		(snapshots, self.next_page_token)
+	}
+}
+
+/// The items of several symbols, a page holds the end of a symbol's items
+/// and the start of the next one's. Symbols are sorted like the API sorts
+/// them.
+fn by_symbol<T>(items: HashMap<String, Vec<T>>) -> Vec<(String, T)> {
+	let mut items = items.into_iter().collect::<Vec<_>>();
+	items.sort_by(|(a, _), (b, _)| a.cmp(b));
+	items.into_iter().flat_map(|(symbol, items)| items.into_iter().map(move |item| (symbol.clone(), item))).collect()
+}
+
+/// Stream the items of every page, fetching the next page once the previous
+/// one is consumed. `fetch` gets the page token, `None` for the first page.
+/// No page is fetched past `max_items` items.
+pub fn paginate<P, E, F, Fut>(max_items: Option<usize>, mut fetch: F) -> impl Stream<Item = Result<P::Item, E>>
+where
+	P: Page,
+	F: FnMut(Option<String>) -> Fut,
+	Fut: Future<Output = Result<P, E>>,
+{
+	// `None` once the last page is fetched.
+	let first: Option<Option<String>> = Some(None);
+	stream::try_unfold(first, move |token| {
+		let page = token.map(&mut fetch);
+		async move {
+			let Some(page) = page else {
+				return Ok(None);
+			};
+			let (items, next) = page.await?.into_parts();
+			Ok(Some((items, next.filter(|token| !token.is_empty()).map(Some))))
+		}
+	})
+	.map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+	.try_flatten()
+	.take(max_items.unwrap_or(usize::MAX))
+}
+
+/// Collect a multi-symbol stream into a map, keeping each symbol's order.
+///
+/// # Errors
+///
+/// Fails with the first error of the stream.
+pub async fn group_by_symbol<S, T, E>(items: S) -> Result<HashMap<String, Vec<T>>, E>
+where
+	S: Stream<Item = Result<(String, T), E>>,
+{
+	items
+		.try_fold(HashMap::<String, Vec<T>>::new(), |mut map, (symbol, item)| async move {
+			map.entry(symbol).or_default().push(item);
+			Ok(map)
+		})
+		.await
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		chrono::{TimeZone, Utc},
+		std::future::ready,
+	};
+
+	struct Numbers(Vec<u32>, Option<&'static str>);
+
+	impl Page for Numbers {
+		type Item = u32;
+
+		fn into_parts(self) -> (Vec<Self::Item>, Option<String>) {
+			(self.0, self.1.map(str::to_owned))
+		}
+	}
+
+	/// Collect the items and the tokens of the fetched pages, `pages` being
+	/// keyed by token.
+	async fn run(max_items: Option<usize>, pages: fn(Option<&str>) -> Numbers) -> (Vec<u32>, Vec<Option<String>>) {
+		let mut tokens = Vec::new();
+		let items = paginate(max_items, |token: Option<String>| {
+			let page = pages(token.as_deref());
+			tokens.push(token);
+			ready(Ok::<_, ()>(page))
+		})
+		.map(Result::unwrap)
+		.collect()
+		.await;
+		(items, tokens)
+	}
+
+	#[tokio::test]
+	async fn pages_follow_their_tokens() {
+		let pages = |token: Option<&str>| match token {
+			None => Numbers(vec![1, 2], Some("a")),
+			Some("a") => Numbers(vec![3], Some("b")),
+			_ => Numbers(vec![4], None),
+		};
+		assert_eq!(run(None, pages).await, (vec![1, 2, 3, 4], vec![None, Some("a".to_owned()), Some("b".to_owned())]));
+	}
+
+	#[tokio::test]
+	async fn empty_tokens_end_the_stream() {
+		let pages = |token: Option<&str>| match token {
+			None => Numbers(vec![1, 2], Some("")),
+			_ => unreachable!("fetched past an empty token"),
+		};
+		assert_eq!(run(None, pages).await, (vec![1, 2], vec![None]));
+	}
+
+	#[tokio::test]
+	async fn max_items_stops_fetching() {
+		let pages = |token: Option<&str>| match token {
+			None => Numbers(vec![1, 2], Some("a")),
+			_ => unreachable!("fetched past max_items"),
+		};
+		assert_eq!(run(Some(2), pages).await, (vec![1, 2], vec![None]));
+		assert_eq!(run(Some(1), pages).await, (vec![1], vec![None]));
+	}
+
+	#[tokio::test]
+	async fn errors_end_the_stream() {
+		let items = paginate(None, |_| ready(Err::<Numbers, _>("down"))).collect::<Vec<_>>().await;
+		assert_eq!(items, [Err("down")]);
+	}
+
+	#[tokio::test]
+	async fn symbols_merge_across_pages() {
+		let bar = |minute: u32| NewBar {
+			time: Utc.with_ymd_and_hms(2024, 1, 2, 15, minute, 0).unwrap(),
+			open: 1.0,
+			close: 1.0,
+			high: 1.0,
+			low: 1.0,
+			volume: 1,
+			trade_count: 1,
+			weighted_average: 1.0,
+		};
+		let first = BarsMultiApiDTO {
+			bars: HashMap::from([("MSFT".to_owned(), vec![bar(0)]), ("AAPL".to_owned(), vec![bar(0), bar(1)])]),
+			next_page_token: Some("a".to_owned()),
+		};
+		let second = BarsMultiApiDTO { bars: HashMap::from([("MSFT".to_owned(), vec![bar(1), bar(2)])]), next_page_token: None };
+		let mut pages = vec![second, first];
+		let stream = paginate(None, |_| ready(Ok::<_, ()>(pages.pop().unwrap())));
+		let bars = group_by_symbol(stream).await.unwrap();
+		assert_eq!(bars["AAPL"], [bar(0), bar(1)]);
+		assert_eq!(bars["MSFT"], [bar(0), bar(1), bar(2)]);
+		assert_eq!(bars.len(), 2);
+	}
+}
@@ -0,0 +1,4 @@
+pub mod quotes_dtos;
+
+#[cfg(feature = "server")]
+pub mod quotes_reqwest;
@@ -1,5 +1,5 @@
 use {
-	super::{enums::feed::Feed, last_quotes_dtos::QuoteDTO},
+	crate::data::{enums::feed::Feed, last_quotes::last_quotes_dtos::QuoteDTO},
 	chrono::{DateTime, Utc},
 	serde::{Deserialize, Serialize},
 };
@@ -16,7 +16,7 @@ pub struct QuotesRequestDTO {
 	pub page_token: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct QuotesDTO {
 	// #[serde(deserialize_with = "vec_from_str")]
 	pub quotes: Vec<QuoteDTO>,
@@ -0,0 +1,45 @@
+use {
+	super::quotes_dtos::{QuotesDTO, QuotesRequestDTO},
+	crate::{
+		data::{enums::feed::Feed, last_quotes::last_quotes_dtos::QuoteDTO, pagination::paginate},
+		error::AlpacaResult,
+		get_client::AlpacaClient,
+		routes::EDataRoute,
+	},
+	chrono::{DateTime, Utc},
+	futures::Stream,
+};
+
+#[bon::builder]
+pub async fn quotes_request(
+	client: &AlpacaClient,
+	symbol: String,
+	start: DateTime<Utc>,
+	end: DateTime<Utc>,
+	limit: Option<usize>,
+	feed: Option<Feed>,
+	page_token: Option<String>,
+) -> AlpacaResult<QuotesDTO> {
+	client.send(client.get(client.url(EDataRoute::Quotes(symbol))).query(&QuotesRequestDTO { start, end, limit, feed, page_token })).await
+}
+
+/// Quotes of `symbol` across all pages. `limit` is the page size, up to 10000,
+/// `max_items` caps the total.
+#[bon::builder]
+pub fn quotes_stream(
+	client: AlpacaClient,
+	symbol: String,
+	start: DateTime<Utc>,
+	end: DateTime<Utc>,
+	limit: Option<usize>,
+	max_items: Option<usize>,
+	feed: Option<Feed>,
+) -> impl Stream<Item = AlpacaResult<QuoteDTO>> {
+	paginate(max_items, move |page_token| {
+		let client = client.clone();
+		let symbol = symbol.clone();
+		async move {
+			quotes_request().client(&client).symbol(symbol).start(start).end(end).maybe_limit(limit).maybe_feed(feed).maybe_page_token(page_token).call().await
+		}
+	})
+}
@@ -0,0 +1,4 @@
+pub mod stream_dtos;
+
+#[cfg(feature = "server")]
+pub mod stream_websocket;
@@ -0,0 +1,227 @@
+use {
+	crate::data::{bars::bars_dtos::NewBar, last_quotes::last_quotes_dtos::QuoteDTO, trades::trades_dtos::TradeDTO},
+	serde::{Deserialize, Serialize},
+	serde_json::Value,
+	std::collections::BTreeSet,
+};
+
+/// A market data message, tagged by `T` on the wire.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "T")]
+pub enum MarketData {
+	#[serde(rename = "t")]
+	Trade {
+		#[serde(rename = "S")]
+		symbol: String,
+		#[serde(flatten)]
+		trade: TradeDTO,
+	},
+	#[serde(rename = "q")]
+	Quote {
+		#[serde(rename = "S")]
+		symbol: String,
+		#[serde(flatten)]
+		quote: QuoteDTO,
+	},
+	/// A minute bar.
+	#[serde(rename = "b")]
+	Bar {
+		#[serde(rename = "S")]
+		symbol: String,
+		#[serde(flatten)]
+		bar: NewBar,
+	},
+	/// The bar of the current day so far, sent after each minute bar.
+	#[serde(rename = "d")]
+	DailyBar {
+		#[serde(rename = "S")]
+		symbol: String,
+		#[serde(flatten)]
+		bar: NewBar,
+	},
+	/// A minute bar corrected by late trades.
+	#[serde(rename = "u")]
+	UpdatedBar {
+		#[serde(rename = "S")]
+		symbol: String,
+		#[serde(flatten)]
+		bar: NewBar,
+	},
+}
+
+impl MarketData {
+	pub fn symbol(&self) -> &str {
+		match self {
+			Self::Trade { symbol, .. } | Self::Quote { symbol, .. } | Self::Bar { symbol, .. } | Self::DailyBar { symbol, .. } | Self::UpdatedBar { symbol, .. } =>
+				symbol,
+		}
+	}
+}
+
+/// Symbols per channel, `*` subscribes to every symbol.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Subscriptions {
+	#[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+	pub trades: BTreeSet<String>,
+	#[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+	pub quotes: BTreeSet<String>,
+	#[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+	pub bars: BTreeSet<String>,
+	#[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+	pub daily_bars: BTreeSet<String>,
+	#[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+	pub updated_bars: BTreeSet<String>,
+}
+
+impl Subscriptions {
+	pub fn trades<I, S>(mut self, symbols: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: Into<String>,
+	{
+		self.trades.extend(symbols.into_iter().map(Into::into));
+		self
+	}
+
+	pub fn quotes<I, S>(mut self, symbols: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: Into<String>,
+	{
+		self.quotes.extend(symbols.into_iter().map(Into::into));
+		self
+	}
+
+	pub fn bars<I, S>(mut self, symbols: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: Into<String>,
+	{
+		self.bars.extend(symbols.into_iter().map(Into::into));
+		self
+	}
+
+	pub fn daily_bars<I, S>(mut self, symbols: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: Into<String>,
+	{
+		self.daily_bars.extend(symbols.into_iter().map(Into::into));
+		self
+	}
+
+	pub fn updated_bars<I, S>(mut self, symbols: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: Into<String>,
+	{
+		self.updated_bars.extend(symbols.into_iter().map(Into::into));
+		self
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.channels().iter().all(|symbols| symbols.is_empty())
+	}
+
+	/// Add the symbols of `other`.
+	pub fn merge(&mut self, other: &Self) {
+		for (symbols, added) in self.channels_mut().into_iter().zip(other.channels()) {
+			symbols.extend(added.iter().cloned());
+		}
+	}
+
+	/// Remove the symbols of `other`.
+	pub fn remove(&mut self, other: &Self) {
+		for (symbols, removed) in self.channels_mut().into_iter().zip(other.channels()) {
+			symbols.retain(|symbol| !removed.contains(symbol));
+		}
+	}
+
+	fn channels(&self) -> [&BTreeSet<String>; 5] {
+		[&self.trades, &self.quotes, &self.bars, &self.daily_bars, &self.updated_bars]
+	}
+
+	fn channels_mut(&mut self) -> [&mut BTreeSet<String>; 5] {
+		[&mut self.trades, &mut self.quotes, &mut self.bars, &mut self.daily_bars, &mut self.updated_bars]
+	}
+}
+
+/// The `subscribe` and `unsubscribe` messages.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SubscriptionRequest<'s> {
+	pub action: SubscriptionAction,
+	#[serde(flatten)]
+	pub subscriptions: &'s Subscriptions,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubscriptionAction {
+	Subscribe,
+	Unsubscribe,
+}
+
+/// Messages about the connection itself.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "T", rename_all = "lowercase")]
+pub enum ControlMessage {
+	/// `connected` or `authenticated`.
+	Success {
+		msg: String,
+	},
+	Error {
+		code: u16,
+		msg: String,
+	},
+	/// Every current subscription, sent after each change.
+	Subscription(Subscriptions),
+	/// Trading statuses, corrections and other messages not handled here.
+	#[serde(other)]
+	Other,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamMessage {
+	Data(MarketData),
+	Control(ControlMessage),
+}
+
+impl StreamMessage {
+	/// Decode a frame, which holds an array of messages.
+	///
+	/// # Errors
+	///
+	/// Fails when the frame is not an array or a market data message does
+	/// not match its type.
+	pub fn decode(frame: &[u8]) -> Result<Vec<Self>, serde_json::Error> {
+		serde_json::from_slice::<Vec<Value>>(frame)?
+			.into_iter()
+			.map(|message| match message.get("T").and_then(Value::as_str) {
+				Some("t" | "q" | "b" | "d" | "u") => serde_json::from_value(message).map(Self::Data),
+				_ => serde_json::from_value(message).map(Self::Control),
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn frames_split_into_data_and_control() {
+		let frame = br#"[{"T":"success","msg":"connected"},{"T":"n","S":"AAPL"},{"T":"subscription","bars":["AAPL"]},{"T":"d","S":"AAPL","t":"2024-01-02T21:00:00Z","o":1,"c":2,"h":3,"l":0.5,"v":10,"n":2,"vw":1.5}]"#;
+		let messages = StreamMessage::decode(frame).unwrap();
+		assert_eq!(messages[0], StreamMessage::Control(ControlMessage::Success { msg: "connected".to_owned() }));
+		assert_eq!(messages[1], StreamMessage::Control(ControlMessage::Other));
+		assert_eq!(messages[2], StreamMessage::Control(ControlMessage::Subscription(Subscriptions::default().bars(["AAPL"]))));
+		assert!(matches!(&messages[3], StreamMessage::Data(data @ MarketData::DailyBar { .. }) if data.symbol() == "AAPL"));
+	}
+
+	#[test]
+	fn malformed_frames_fail() {
+		assert!(StreamMessage::decode(br#"{"T":"success","msg":"connected"}"#).is_err());
+		assert!(StreamMessage::decode(br#"[{"T":"t","S":"AAPL"}]"#).is_err());
+	}
+}
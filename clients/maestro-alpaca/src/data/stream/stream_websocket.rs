@@ -0,0 +1,276 @@
+use {
+	super::stream_dtos::{ControlMessage, MarketData, StreamMessage, SubscriptionAction, SubscriptionRequest, Subscriptions},
+	crate::{
+		api::enums::updates::Authenticate,
+		data::enums::feed::Feed,
+		error::{AlpacaError, AlpacaResult},
+		get_client::AlpacaClient,
+		routes::EStreamRoute,
+		websocket::{self, ReconnectPolicy, Socket},
+	},
+	futures::Stream,
+	std::{
+		pin::Pin,
+		task::{Context, Poll},
+	},
+	tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel},
+};
+
+/// Real-time market data. The connection runs in a task that stops when the
+/// stream is dropped or fails for good, which is the last item.
+#[derive(Debug)]
+pub struct MarketDataStream {
+	items: UnboundedReceiver<AlpacaResult<MarketData>>,
+}
+
+impl Stream for MarketDataStream {
+	type Item = AlpacaResult<MarketData>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		self.items.poll_recv(cx)
+	}
+}
+
+/// Changes the subscriptions of a [`MarketDataStream`]. They are kept across
+/// reconnections.
+#[derive(Debug, Clone)]
+pub struct MarketDataHandle {
+	commands: UnboundedSender<(SubscriptionAction, Subscriptions)>,
+}
+
+impl MarketDataHandle {
+	/// # Errors
+	///
+	/// Fails when the stream is closed.
+	pub fn subscribe(&self, subscriptions: Subscriptions) -> AlpacaResult<()> {
+		self.send(SubscriptionAction::Subscribe, subscriptions)
+	}
+
+	/// # Errors
+	///
+	/// Fails when the stream is closed.
+	pub fn unsubscribe(&self, subscriptions: Subscriptions) -> AlpacaResult<()> {
+		self.send(SubscriptionAction::Unsubscribe, subscriptions)
+	}
+
+	fn send(&self, action: SubscriptionAction, subscriptions: Subscriptions) -> AlpacaResult<()> {
+		self.commands.send((action, subscriptions)).map_err(|_| AlpacaError::StreamClosed)
+	}
+}
+
+#[bon::bon]
+impl AlpacaClient {
+	/// Connect to the stock data stream of `feed` at [`AlpacaUrls::stream`](crate::alpaca_env::AlpacaUrls::stream).
+	/// Must be called within a Tokio runtime.
+	#[builder(finish_fn = connect)]
+	pub fn market_data_stream(
+		&self,
+		#[builder(default)] feed: Feed,
+		#[builder(default)] subscriptions: Subscriptions,
+		#[builder(default)] reconnect: ReconnectPolicy,
+	) -> (MarketDataHandle, MarketDataStream) {
+		let (commands_tx, commands) = unbounded_channel();
+		let (items_tx, items) = unbounded_channel();
+		let connection = Connection {
+			url: self.url(EStreamRoute::MarketData(feed)),
+			auth: self.authenticate(),
+			reconnect,
+			subscriptions,
+			commands,
+			commands_open: true,
+			items: items_tx,
+		};
+		tokio::spawn(connection.run());
+		(MarketDataHandle { commands: commands_tx }, MarketDataStream { items })
+	}
+}
+
+struct Connection {
+	url: String,
+	auth: Authenticate<'static>,
+	reconnect: ReconnectPolicy,
+	subscriptions: Subscriptions,
+	commands: UnboundedReceiver<(SubscriptionAction, Subscriptions)>,
+	/// Whether a [`MarketDataHandle`] is left.
+	commands_open: bool,
+	items: UnboundedSender<AlpacaResult<MarketData>>,
+}
+
+impl Connection {
+	async fn run(mut self) {
+		let mut attempt = 0;
+		loop {
+			let error = match self.session(&mut attempt).await {
+				Ok(()) => return,
+				Err(error) => error,
+			};
+			let Some(delay) = (!error.is_fatal()).then(|| self.reconnect.delay(attempt)).flatten() else {
+				let _ = self.items.send(Err(error));
+				return;
+			};
+			attempt += 1;
+			tokio::select! {
+				() = self.items.closed() => return,
+				() = tokio::time::sleep(delay) => {},
+			}
+		}
+	}
+
+	/// Connect, authenticate, subscribe and forward messages. Returns `Ok`
+	/// once the stream is dropped.
+	async fn session(&mut self, attempt: &mut u32) -> AlpacaResult<()> {
+		let mut socket = websocket::connect(&self.url).await?;
+		expect_success(&mut socket, "connected").await?;
+		websocket::send_json(&mut socket, &self.auth).await?;
+		expect_success(&mut socket, "authenticated").await?;
+		*attempt = 0;
+		if !self.subscriptions.is_empty() {
+			websocket::send_json(&mut socket, &SubscriptionRequest { action: SubscriptionAction::Subscribe, subscriptions: &self.subscriptions }).await?;
+		}
+		loop {
+			tokio::select! {
+				() = self.items.closed() => return Ok(()),
+				command = self.commands.recv(), if self.commands_open => match command {
+					Some((action, subscriptions)) => {
+						match action {
+							SubscriptionAction::Subscribe => self.subscriptions.merge(&subscriptions),
+							SubscriptionAction::Unsubscribe => self.subscriptions.remove(&subscriptions),
+						}
+						websocket::send_json(&mut socket, &SubscriptionRequest { action, subscriptions: &subscriptions }).await?;
+					},
+					None => self.commands_open = false,
+				},
+				frame = websocket::next_frame(&mut socket) => self.dispatch(&frame?)?,
+			}
+		}
+	}
+
+	/// Forward the messages of `frame`. Errors about a request are forwarded
+	/// too, the others end the session.
+	fn dispatch(&mut self, frame: &[u8]) -> AlpacaResult<()> {
+		let messages = match StreamMessage::decode(frame) {
+			Ok(messages) => messages,
+			Err(error) => {
+				let _ = self.items.send(Err(error.into()));
+				return Ok(());
+			},
+		};
+		for message in messages {
+			match message {
+				StreamMessage::Data(data) => {
+					let _ = self.items.send(Ok(data));
+				},
+				StreamMessage::Control(ControlMessage::Subscription(subscriptions)) => self.subscriptions = subscriptions,
+				StreamMessage::Control(ControlMessage::Error { code, msg }) => {
+					let error = AlpacaError::Stream { code, message: msg };
+					match code {
+						// Invalid syntax, symbol limit exceeded and invalid subscribe action.
+						400 | 405 | 410 => {
+							let _ = self.items.send(Err(error));
+						},
+						_ => return Err(error),
+					}
+				},
+				StreamMessage::Control(ControlMessage::Success { .. } | ControlMessage::Other) => {},
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Read until the success message `expected`.
+async fn expect_success(socket: &mut Socket, expected: &str) -> AlpacaResult<()> {
+	loop {
+		for message in StreamMessage::decode(&websocket::next_frame(socket).await?)? {
+			match message {
+				StreamMessage::Control(ControlMessage::Success { msg }) if msg == expected => return Ok(()),
+				StreamMessage::Control(ControlMessage::Error { code, msg }) => return Err(AlpacaError::Stream { code, message: msg }),
+				_ => {},
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		crate::alpaca_env::AlpacaUrls,
+		futures::{SinkExt, StreamExt},
+		serde_json::{Value, json},
+		std::time::Duration,
+		tokio::net::{TcpListener, TcpStream},
+		tokio_tungstenite::{WebSocketStream, tungstenite::Message},
+	};
+
+	type Server = WebSocketStream<TcpStream>;
+
+	async fn send(socket: &mut Server, messages: Value) {
+		socket.send(Message::text(messages.to_string())).await.unwrap();
+	}
+
+	async fn receive(socket: &mut Server) -> Value {
+		loop {
+			if let Message::Text(text) = socket.next().await.unwrap().unwrap() {
+				return serde_json::from_str(&text).unwrap();
+			}
+		}
+	}
+
+	/// Accept a connection, go through the handshake and return the subscribe
+	/// request.
+	async fn accept(listener: &TcpListener) -> (Server, Value) {
+		let (stream, _) = listener.accept().await.unwrap();
+		let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+		send(&mut socket, json!([{ "T": "success", "msg": "connected" }])).await;
+		assert_eq!(receive(&mut socket).await, json!({ "action": "auth", "key": "key", "secret": "secret" }));
+		send(&mut socket, json!([{ "T": "success", "msg": "authenticated" }])).await;
+		let subscribe = receive(&mut socket).await;
+		(socket, subscribe)
+	}
+
+	#[tokio::test]
+	async fn resubscribes_after_reconnecting() {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let urls = AlpacaUrls::builder().api(AlpacaUrls::PAPER).stream(format!("ws://{}", listener.local_addr().unwrap())).build();
+		let subscribe = json!({ "action": "subscribe", "trades": ["AAPL"] });
+		let server = tokio::spawn(async move {
+			let (mut socket, request) = accept(&listener).await;
+			assert_eq!(request, subscribe);
+			send(
+				&mut socket,
+				json!([
+					{ "T": "subscription", "trades": ["AAPL"] },
+					{ "T": "t", "S": "AAPL", "t": "2024-01-02T14:30:00Z", "p": 185.5, "s": 100, "x": "V", "i": 1, "c": ["@"], "z": "C" },
+				]),
+			)
+			.await;
+			send(&mut socket, json!([{ "T": "error", "code": 405, "msg": "symbol limit exceeded" }])).await;
+			socket.close(None).await.unwrap();
+
+			let (mut socket, request) = accept(&listener).await;
+			assert_eq!(request, subscribe);
+			send(
+				&mut socket,
+				json!([{ "T": "b", "S": "AAPL", "t": "2024-01-02T14:31:00Z", "o": 185.5, "c": 186.0, "h": 186.5, "l": 185.0, "v": 1200, "n": 12, "vw": 185.8 }]),
+			)
+			.await;
+			send(&mut socket, json!([{ "T": "error", "code": 406, "msg": "connection limit exceeded" }])).await;
+		});
+
+		let client = AlpacaClient::builder().key_id("key").secret("secret").urls(urls).build().unwrap();
+		let (_handle, stream) = client
+			.market_data_stream()
+			.subscriptions(Subscriptions::default().trades(["AAPL"]))
+			.reconnect(ReconnectPolicy::builder().initial_backoff(Duration::from_millis(1)).build())
+			.connect();
+		let items = tokio::time::timeout(Duration::from_secs(5), stream.collect::<Vec<_>>()).await.unwrap();
+		server.await.unwrap();
+
+		assert_eq!(items.len(), 4, "{items:?}");
+		assert!(matches!(&items[0], Ok(MarketData::Trade { symbol, trade }) if symbol == "AAPL" && trade.id == 1 && trade.size == 100));
+		assert!(matches!(items[1], Err(AlpacaError::Stream { code: 405, .. })));
+		assert!(matches!(&items[2], Ok(MarketData::Bar { symbol, bar }) if symbol == "AAPL" && bar.volume == 1200));
+		assert!(matches!(items[3], Err(AlpacaError::Stream { code: 406, .. })));
+	}
+}
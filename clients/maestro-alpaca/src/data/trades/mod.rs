@@ -0,0 +1,4 @@
+pub mod trades_dtos;
+
+#[cfg(feature = "server")]
+pub mod trades_reqwest;
@@ -1,5 +1,5 @@
 use {
-	super::enums::feed::Feed,
+	crate::data::enums::feed::Feed,
 	bigdecimal::BigDecimal,
 	chrono::{DateTime, Utc},
 	serde::{Deserialize, Serialize},
@@ -19,7 +19,7 @@ pub struct TradesRequestDTO {
 	pub page_token: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TradeDTO {
 	#[serde(rename = "t")]
 	pub timestamp: DateTime<Utc>,
@@ -27,6 +27,11 @@ pub struct TradeDTO {
 	pub price: BigDecimal,
 	#[serde(rename = "s")]
 	pub size: usize,
+	#[serde(rename = "x")]
+	pub exchange: String,
+	/// Unique per symbol and exchange.
+	#[serde(rename = "i")]
+	pub id: u64,
 }
 
 #[derive(Debug, PartialEq, Eq, Deserialize)]
@@ -0,0 +1,54 @@
+use {
+	super::trades_dtos::{TradeDTO, TradesDTO, TradesRequestDTO},
+	crate::{
+		data::{enums::feed::Feed, pagination::paginate},
+		error::AlpacaResult,
+		get_client::AlpacaClient,
+		routes::EDataRoute,
+	},
+	chrono::{DateTime, Utc},
+	futures::Stream,
+};
+
+#[bon::builder]
+pub async fn trades_request(
+	client: &AlpacaClient,
+	symbol: String,
+	start: Option<DateTime<Utc>>,
+	end: Option<DateTime<Utc>>,
+	limit: Option<usize>,
+	feed: Option<Feed>,
+	page_token: Option<String>,
+) -> AlpacaResult<TradesDTO> {
+	client.send(client.get(client.url(EDataRoute::Trades(symbol))).query(&TradesRequestDTO { start, end, limit, feed, page_token })).await
+}
+
+/// Trades of `symbol` across all pages. `limit` is the page size, up to 10000,
+/// `max_items` caps the total.
+#[bon::builder]
+pub fn trades_stream(
+	client: AlpacaClient,
+	symbol: String,
+	start: Option<DateTime<Utc>>,
+	end: Option<DateTime<Utc>>,
+	limit: Option<usize>,
+	max_items: Option<usize>,
+	feed: Option<Feed>,
+) -> impl Stream<Item = AlpacaResult<TradeDTO>> {
+	paginate(max_items, move |page_token| {
+		let client = client.clone();
+		let symbol = symbol.clone();
+		async move {
+			trades_request()
+				.client(&client)
+				.symbol(symbol)
+				.maybe_start(start)
+				.maybe_end(end)
+				.maybe_limit(limit)
+				.maybe_feed(feed)
+				.maybe_page_token(page_token)
+				.call()
+				.await
+		}
+	})
+}
@@ -0,0 +1,124 @@
+use {
+	super::{BATCH, schema::alpaca_bars},
+	crate::{
+		data::{bars::bars_dtos::NewBar, enums::timeframe::TimeFrame},
+		error::AlpacaResult,
+	},
+	chrono::{DateTime, Utc},
+	diesel::{prelude::*, upsert::excluded},
+	diesel_async::RunQueryDsl,
+	maestro_diesel::async_client::AsyncDieselPool,
+};
+
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable, Insertable)]
+#[diesel(table_name = alpaca_bars, check_for_backend(diesel::pg::Pg))]
+pub struct BarRow {
+	pub symbol: String,
+	pub timeframe: TimeFrame,
+	pub time: DateTime<Utc>,
+	pub open: f32,
+	pub close: f32,
+	pub high: f32,
+	pub low: f32,
+	pub volume: i32,
+	pub trade_count: i32,
+	pub weighted_average: f32,
+}
+
+impl BarRow {
+	pub fn new(symbol: &str, timeframe: TimeFrame, bar: &NewBar) -> Self {
+		Self {
+			symbol: symbol.to_owned(),
+			timeframe,
+			time: bar.time,
+			open: bar.open,
+			close: bar.close,
+			high: bar.high,
+			low: bar.low,
+			volume: bar.volume,
+			trade_count: bar.trade_count,
+			weighted_average: bar.weighted_average,
+		}
+	}
+}
+
+impl From<BarRow> for NewBar {
+	fn from(row: BarRow) -> Self {
+		Self {
+			time: row.time,
+			open: row.open,
+			close: row.close,
+			high: row.high,
+			low: row.low,
+			volume: row.volume,
+			trade_count: row.trade_count,
+			weighted_average: row.weighted_average,
+		}
+	}
+}
+
+/// Insert `bars`, replacing stored bars with the same symbol, timeframe and
+/// time. Returns the number of rows written.
+///
+/// # Errors
+///
+/// Fails when no connection is available or a query fails.
+pub async fn upsert_bars(pool: AsyncDieselPool, symbol: &str, timeframe: TimeFrame, bars: &[NewBar]) -> AlpacaResult<usize> {
+	let mut conn = pool.get().await?;
+	let mut written = 0;
+	for chunk in bars.chunks(BATCH) {
+		let rows = chunk.iter().map(|bar| BarRow::new(symbol, timeframe, bar)).collect::<Vec<_>>();
+		written += diesel::insert_into(alpaca_bars::table)
+			.values(&rows)
+			.on_conflict((alpaca_bars::symbol, alpaca_bars::timeframe, alpaca_bars::time))
+			.do_update()
+			.set((
+				alpaca_bars::open.eq(excluded(alpaca_bars::open)),
+				alpaca_bars::close.eq(excluded(alpaca_bars::close)),
+				alpaca_bars::high.eq(excluded(alpaca_bars::high)),
+				alpaca_bars::low.eq(excluded(alpaca_bars::low)),
+				alpaca_bars::volume.eq(excluded(alpaca_bars::volume)),
+				alpaca_bars::trade_count.eq(excluded(alpaca_bars::trade_count)),
+				alpaca_bars::weighted_average.eq(excluded(alpaca_bars::weighted_average)),
+			))
+			.execute(&mut conn)
+			.await?;
+	}
+	Ok(written)
+}
+
+/// Stored bars from `start`, inclusive, to `end`, exclusive, oldest first.
+///
+/// # Errors
+///
+/// Fails when no connection is available or the query fails.
+pub async fn bars_range(pool: AsyncDieselPool, symbol: &str, timeframe: TimeFrame, start: DateTime<Utc>, end: DateTime<Utc>) -> AlpacaResult<Vec<NewBar>> {
+	let mut conn = pool.get().await?;
+	let rows = alpaca_bars::table
+		.filter(alpaca_bars::symbol.eq(symbol))
+		.filter(alpaca_bars::timeframe.eq(timeframe))
+		.filter(alpaca_bars::time.ge(start))
+		.filter(alpaca_bars::time.lt(end))
+		.order(alpaca_bars::time.asc())
+		.select(BarRow::as_select())
+		.load(&mut conn)
+		.await?;
+	Ok(rows.into_iter().map(NewBar::from).collect())
+}
+
+/// The time of the last stored bar, where a backfill resumes.
+///
+/// # Errors
+///
+/// Fails when no connection is available or the query fails.
+pub async fn last_bar_time(pool: AsyncDieselPool, symbol: &str, timeframe: TimeFrame) -> AlpacaResult<Option<DateTime<Utc>>> {
+	let mut conn = pool.get().await?;
+	Ok(
+		alpaca_bars::table
+			.filter(alpaca_bars::symbol.eq(symbol))
+			.filter(alpaca_bars::timeframe.eq(timeframe))
+			.select(diesel::dsl::max(alpaca_bars::time))
+			.first(&mut conn)
+			.await?,
+	)
+}
@@ -0,0 +1,12 @@
+//! A Postgres cache of market data and orders. Apply the migrations with the
+//! diesel CLI, the functions take a pool from
+//! [`maestro_diesel::async_client::client::acreate_diesel_pool`].
+
+pub mod bars;
+pub mod orders;
+pub mod quotes;
+pub mod schema;
+pub mod trades;
+
+/// Rows per insert, the bind parameters of a statement are capped at 65535.
+const BATCH: usize = 5000;
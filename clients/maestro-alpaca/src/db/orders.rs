@@ -0,0 +1,85 @@
+use {
+	super::{BATCH, schema::alpaca_orders},
+	crate::{api::order::OrderDTO, error::AlpacaResult},
+	chrono::{DateTime, Utc},
+	diesel::{prelude::*, upsert::excluded},
+	diesel_async::RunQueryDsl,
+	maestro_diesel::async_client::AsyncDieselPool,
+	serde_json::Value,
+	uuid::Uuid,
+};
+
+/// The whole order is kept in `data`, the other columns are for lookups.
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable, Insertable)]
+#[diesel(table_name = alpaca_orders, check_for_backend(diesel::pg::Pg))]
+pub struct OrderRow {
+	pub id: Uuid,
+	pub client_order_id: String,
+	pub symbol: String,
+	pub created_at: DateTime<Utc>,
+	pub updated_at: Option<DateTime<Utc>>,
+	pub data: Value,
+}
+
+impl OrderRow {
+	/// # Errors
+	///
+	/// Fails when the order cannot be serialized.
+	pub fn new(order: &OrderDTO) -> serde_json::Result<Self> {
+		Ok(Self {
+			id: order.id,
+			client_order_id: order.client_order_id.clone(),
+			symbol: order.symbol.clone(),
+			created_at: order.created_at,
+			updated_at: order.updated_at,
+			data: serde_json::to_value(order)?,
+		})
+	}
+}
+
+impl TryFrom<OrderRow> for OrderDTO {
+	type Error = serde_json::Error;
+
+	fn try_from(row: OrderRow) -> Result<Self, Self::Error> {
+		serde_json::from_value(row.data)
+	}
+}
+
+/// Insert `orders`, replacing stored orders with the same ID. Returns the
+/// number of rows written.
+///
+/// # Errors
+///
+/// Fails when no connection is available or a query fails.
+pub async fn upsert_orders(pool: AsyncDieselPool, orders: &[OrderDTO]) -> AlpacaResult<usize> {
+	let mut conn = pool.get().await?;
+	let mut written = 0;
+	for chunk in orders.chunks(BATCH) {
+		let rows = chunk.iter().map(OrderRow::new).collect::<Result<Vec<_>, _>>()?;
+		written += diesel::insert_into(alpaca_orders::table)
+			.values(&rows)
+			.on_conflict(alpaca_orders::id)
+			.do_update()
+			.set((alpaca_orders::updated_at.eq(excluded(alpaca_orders::updated_at)), alpaca_orders::data.eq(excluded(alpaca_orders::data))))
+			.execute(&mut conn)
+			.await?;
+	}
+	Ok(written)
+}
+
+/// Stored orders created from `start`, inclusive, to `end`, exclusive, oldest
+/// first. All symbols when `symbol` is `None`.
+///
+/// # Errors
+///
+/// Fails when no connection is available, the query fails or an order cannot
+/// be decoded.
+pub async fn orders_range(pool: AsyncDieselPool, symbol: Option<&str>, start: DateTime<Utc>, end: DateTime<Utc>) -> AlpacaResult<Vec<OrderDTO>> {
+	let mut conn = pool.get().await?;
+	let mut query = alpaca_orders::table.filter(alpaca_orders::created_at.ge(start)).filter(alpaca_orders::created_at.lt(end)).into_boxed();
+	if let Some(symbol) = symbol {
+		query = query.filter(alpaca_orders::symbol.eq(symbol));
+	}
+	let rows = query.order(alpaca_orders::created_at.asc()).select(OrderRow::as_select()).load(&mut conn).await?;
+	Ok(rows.into_iter().map(OrderDTO::try_from).collect::<Result<_, _>>()?)
+}
@@ -0,0 +1,92 @@
+use {
+	super::{BATCH, schema::alpaca_quotes},
+	crate::{data::last_quotes::last_quotes_dtos::QuoteDTO, error::AlpacaResult},
+	chrono::{DateTime, Utc},
+	diesel::prelude::*,
+	diesel_async::RunQueryDsl,
+	maestro_diesel::async_client::AsyncDieselPool,
+};
+
+/// Quotes have no ID and several can share a timestamp, a quote is
+/// identified by its time and both of its sides.
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable, Insertable)]
+#[diesel(table_name = alpaca_quotes, check_for_backend(diesel::pg::Pg))]
+pub struct QuoteRow {
+	pub symbol: String,
+	pub time: DateTime<Utc>,
+	pub ask_exchange: String,
+	pub ask_price: f32,
+	pub ask_size: i64,
+	pub bid_exchange: String,
+	pub bid_price: f32,
+	pub bid_size: i64,
+	pub conditions: Vec<String>,
+	pub tape: String,
+}
+
+impl QuoteRow {
+	pub fn new(symbol: &str, quote: &QuoteDTO) -> Self {
+		Self {
+			symbol: symbol.to_owned(),
+			time: quote.time,
+			ask_exchange: quote.ask_exchange.clone(),
+			ask_price: quote.ask_price,
+			ask_size: quote.ask_size.into(),
+			bid_exchange: quote.bid_exchange.clone(),
+			bid_price: quote.bid_price,
+			bid_size: quote.bid_size.into(),
+			conditions: quote.conditions.clone(),
+			tape: quote.tape.clone(),
+		}
+	}
+}
+
+impl From<QuoteRow> for QuoteDTO {
+	fn from(row: QuoteRow) -> Self {
+		Self {
+			time: row.time,
+			ask_exchange: row.ask_exchange,
+			ask_price: row.ask_price,
+			ask_size: row.ask_size.try_into().unwrap_or(u32::MAX),
+			bid_exchange: row.bid_exchange,
+			bid_price: row.bid_price,
+			bid_size: row.bid_size.try_into().unwrap_or(u32::MAX),
+			conditions: row.conditions,
+			tape: row.tape,
+		}
+	}
+}
+
+/// Quotes already stored are skipped, so overlapping ranges can be
+/// backfilled again. Returns the number of rows written.
+///
+/// # Errors
+///
+/// Fails when no connection is available or a query fails.
+pub async fn insert_quotes(pool: AsyncDieselPool, symbol: &str, quotes: &[QuoteDTO]) -> AlpacaResult<usize> {
+	let mut conn = pool.get().await?;
+	let mut written = 0;
+	for chunk in quotes.chunks(BATCH) {
+		let rows = chunk.iter().map(|quote| QuoteRow::new(symbol, quote)).collect::<Vec<_>>();
+		written += diesel::insert_into(alpaca_quotes::table).values(&rows).on_conflict_do_nothing().execute(&mut conn).await?;
+	}
+	Ok(written)
+}
+
+/// Stored quotes from `start`, inclusive, to `end`, exclusive, oldest first.
+///
+/// # Errors
+///
+/// Fails when no connection is available or the query fails.
+pub async fn quotes_range(pool: AsyncDieselPool, symbol: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> AlpacaResult<Vec<QuoteDTO>> {
+	let mut conn = pool.get().await?;
+	let rows = alpaca_quotes::table
+		.filter(alpaca_quotes::symbol.eq(symbol))
+		.filter(alpaca_quotes::time.ge(start))
+		.filter(alpaca_quotes::time.lt(end))
+		.order((alpaca_quotes::time.asc(), alpaca_quotes::id.asc()))
+		.select(QuoteRow::as_select())
+		.load(&mut conn)
+		.await?;
+	Ok(rows.into_iter().map(QuoteDTO::from).collect())
+}
@@ -0,0 +1,80 @@
+use {
+	super::{BATCH, schema::alpaca_trades},
+	crate::{data::trades::trades_dtos::TradeDTO, error::AlpacaResult},
+	bigdecimal::BigDecimal,
+	chrono::{DateTime, Utc},
+	diesel::prelude::*,
+	diesel_async::RunQueryDsl,
+	maestro_diesel::async_client::AsyncDieselPool,
+};
+
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable, Insertable)]
+#[diesel(table_name = alpaca_trades, check_for_backend(diesel::pg::Pg))]
+pub struct TradeRow {
+	pub symbol: String,
+	pub time: DateTime<Utc>,
+	pub price: BigDecimal,
+	pub size: i64,
+	pub exchange: String,
+	pub trade_id: i64,
+}
+
+impl TradeRow {
+	pub fn new(symbol: &str, trade: &TradeDTO) -> Self {
+		Self {
+			symbol: symbol.to_owned(),
+			time: trade.timestamp,
+			price: trade.price.clone(),
+			size: trade.size.try_into().unwrap_or(i64::MAX),
+			exchange: trade.exchange.clone(),
+			trade_id: trade.id.try_into().unwrap_or(i64::MAX),
+		}
+	}
+}
+
+impl From<TradeRow> for TradeDTO {
+	fn from(row: TradeRow) -> Self {
+		Self {
+			timestamp: row.time,
+			price: row.price,
+			size: row.size.try_into().unwrap_or_default(),
+			exchange: row.exchange,
+			id: row.trade_id.try_into().unwrap_or_default(),
+		}
+	}
+}
+
+/// Trades already stored, identified by their time, exchange and ID, are
+/// skipped, so overlapping ranges can be backfilled again. Returns the number
+/// of rows written.
+///
+/// # Errors
+///
+/// Fails when no connection is available or a query fails.
+pub async fn insert_trades(pool: AsyncDieselPool, symbol: &str, trades: &[TradeDTO]) -> AlpacaResult<usize> {
+	let mut conn = pool.get().await?;
+	let mut written = 0;
+	for chunk in trades.chunks(BATCH) {
+		let rows = chunk.iter().map(|trade| TradeRow::new(symbol, trade)).collect::<Vec<_>>();
+		written += diesel::insert_into(alpaca_trades::table).values(&rows).on_conflict_do_nothing().execute(&mut conn).await?;
+	}
+	Ok(written)
+}
+
+/// Stored trades from `start`, inclusive, to `end`, exclusive, oldest first.
+///
+/// # Errors
+///
+/// Fails when no connection is available or the query fails.
+pub async fn trades_range(pool: AsyncDieselPool, symbol: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> AlpacaResult<Vec<TradeDTO>> {
+	let mut conn = pool.get().await?;
+	let rows = alpaca_trades::table
+		.filter(alpaca_trades::symbol.eq(symbol))
+		.filter(alpaca_trades::time.ge(start))
+		.filter(alpaca_trades::time.lt(end))
+		.order((alpaca_trades::time.asc(), alpaca_trades::id.asc()))
+		.select(TradeRow::as_select())
+		.load(&mut conn)
+		.await?;
+	Ok(rows.into_iter().map(TradeDTO::from).collect())
+}
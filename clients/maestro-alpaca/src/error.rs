@@ -0,0 +1,138 @@
+use {
+	crate::api::order::OrderValidationError,
+	chrono::{DateTime, Utc},
+	diesel_async::pooled_connection::deadpool::PoolError,
+	reqwest::{
+		Response, StatusCode,
+		header::{HeaderMap, InvalidHeaderValue},
+	},
+	serde::{Deserialize, Serialize},
+	tokio_tungstenite::tungstenite,
+};
+
+pub type AlpacaResult<T> = Result<T, AlpacaError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AlpacaError {
+	#[error("HTTP error: {0}")]
+	Http(#[from] reqwest::Error),
+	#[error("Decode error: {0}")]
+	Decode(#[from] serde_json::Error),
+	/// Found before sending the order.
+	#[error("Invalid order: {0}")]
+	InvalidOrder(#[from] OrderValidationError),
+	/// 401, the credentials are wrong or missing.
+	#[error("Unauthorized: {message}")]
+	Unauthorized { message: String },
+	/// 403, the account cannot do this, like trading without buying power or
+	/// reading SIP data without a subscription.
+	#[error("Forbidden: {message}")]
+	Forbidden { message: String },
+	#[error("Not found: {message}")]
+	NotFound { message: String },
+	/// 422, the order or its parameters were rejected. `code` is Alpaca's own
+	/// error code.
+	#[error("Unprocessable order: {message}")]
+	UnprocessableOrder { code: Option<u64>, message: String },
+	/// 429, too many requests. `reset` is when the quota is restored, from
+	/// the `x-ratelimit-reset` header.
+	#[error("Rate limited until {}", reset.map_or_else(|| "an unknown time".to_owned(), |reset| reset.to_rfc3339()))]
+	RateLimited { reset: Option<DateTime<Utc>> },
+	/// Any other non-success response. `code` is Alpaca's own error code.
+	#[error("Alpaca error ({status}): {message}")]
+	Api { status: u16, code: Option<u64>, message: String },
+	#[error("Missing credentials, pass them or set `{var}`")]
+	MissingCredentials { var: &'static str },
+	#[error("Invalid credentials: {0}")]
+	InvalidCredentials(#[from] InvalidHeaderValue),
+	#[error("Websocket error: {0}")]
+	WebSocket(Box<tungstenite::Error>),
+	/// An error message sent on a stream. `code` is Alpaca's own error code.
+	#[error("Stream error ({code}): {message}")]
+	Stream { code: u16, message: String },
+	#[error("The server closed the stream")]
+	Disconnected,
+	#[error("Database error: {0}")]
+	Database(#[from] diesel::result::Error),
+	#[error("Database pool error: {0}")]
+	Pool(#[from] PoolError),
+	/// The task running a stream has stopped.
+	#[error("The stream is closed")]
+	StreamClosed,
+}
+
+impl AlpacaError {
+	pub const RATE_LIMIT_RESET_HEADER: &'static str = "x-ratelimit-reset";
+
+	/// Read the error of a non-success response.
+	pub async fn from_response(response: Response) -> Self {
+		let status = response.status();
+		let reset = Self::rate_limit_reset(response.headers());
+		let body = match response.text().await {
+			Ok(body) => body,
+			Err(error) => return error.into(),
+		};
+		let ApiErrorBody { code, message } = serde_json::from_str(&body).unwrap_or(ApiErrorBody { code: None, message: body });
+		match status {
+			StatusCode::UNAUTHORIZED => Self::Unauthorized { message },
+			StatusCode::FORBIDDEN => Self::Forbidden { message },
+			StatusCode::NOT_FOUND => Self::NotFound { message },
+			StatusCode::UNPROCESSABLE_ENTITY => Self::UnprocessableOrder { code, message },
+			StatusCode::TOO_MANY_REQUESTS => Self::RateLimited { reset },
+			_ => Self::Api { status: status.as_u16(), code, message },
+		}
+	}
+
+	/// The header holds a Unix timestamp in seconds.
+	fn rate_limit_reset(headers: &HeaderMap) -> Option<DateTime<Utc>> {
+		let seconds = headers.get(Self::RATE_LIMIT_RESET_HEADER)?.to_str().ok()?.trim().parse().ok()?;
+		DateTime::from_timestamp(seconds, 0)
+	}
+
+	/// Stream errors that reconnecting cannot fix: rejected credentials or
+	/// permissions, too many connections and missing market data
+	/// subscription.
+	pub fn is_fatal(&self) -> bool {
+		matches!(self, Self::Unauthorized { .. } | Self::Forbidden { .. } | Self::Stream { code: 402 | 406 | 408 | 409, .. })
+	}
+}
+
+impl From<tungstenite::Error> for AlpacaError {
+	fn from(error: tungstenite::Error) -> Self {
+		Self::WebSocket(Box::new(error))
+	}
+}
+
+/// The body of error responses.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiErrorBody {
+	#[serde(default)]
+	pub code: Option<u64>,
+	pub message: String,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rejected_credentials_are_fatal() {
+		assert!(AlpacaError::Unauthorized { message: "access key verification failed".to_owned() }.is_fatal());
+		assert!(AlpacaError::Forbidden { message: "forbidden".to_owned() }.is_fatal());
+		assert!(AlpacaError::Stream { code: 406, message: "connection limit exceeded".to_owned() }.is_fatal());
+		assert!(!AlpacaError::Disconnected.is_fatal());
+	}
+
+	#[test]
+	fn rate_limit_resets_are_unix_seconds() {
+		let reset = |value: &str| {
+			let mut headers = HeaderMap::new();
+			headers.insert(AlpacaError::RATE_LIMIT_RESET_HEADER, value.parse().unwrap());
+			AlpacaError::rate_limit_reset(&headers)
+		};
+		assert_eq!(reset("1704207600"), DateTime::from_timestamp(1_704_207_600, 0));
+		assert_eq!(reset(" 1704207600 ").map(|reset| reset.to_rfc3339()).as_deref(), Some("2024-01-02T15:00:00+00:00"));
+		assert_eq!(reset("soon"), None);
+		assert_eq!(AlpacaError::rate_limit_reset(&HeaderMap::new()), None);
+	}
+}
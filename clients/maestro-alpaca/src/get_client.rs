@@ -1,37 +1,294 @@
 use {
+	crate::{
+		alpaca_env::AlpacaUrls,
+		api::enums::updates::Authenticate,
+		error::{AlpacaError, AlpacaResult},
+		routes::{EAlpacaRoute, EApiRoute},
+	},
+	chrono::Utc,
 	reqwest::{
+		Client, Method, RequestBuilder, Response,
 		header::{HeaderName, HeaderValue},
-		Client,
 	},
-	std::ops::Deref,
+	serde::de::DeserializeOwned,
+	std::{ops::Deref, time::Duration},
 };
 
 const HDR_KEY_ID: &str = "apca-api-key-id";
 const HDR_SECRET: &str = "apca-api-secret-key";
 
+/// An authenticated client. It derefs to the inner [`Client`] for the
+/// `data` functions, the trading endpoints are methods.
 #[derive(Clone)]
-pub struct AlpacaClient(Client);
+pub struct AlpacaClient {
+	client: Client,
+	urls: AlpacaUrls,
+	key_id: String,
+	secret: String,
+	retry: RetryPolicy,
+}
 
 impl Deref for AlpacaClient {
 	type Target = Client;
 
 	fn deref(&self) -> &Self::Target {
-		&self.0
+		&self.client
 	}
 }
 
-pub fn get_alpaca_reqwest_client(key_id: &str, secret: &str) -> AlpacaClient {
-	AlpacaClient(
-		Client::builder()
+#[bon::bon]
+impl AlpacaClient {
+	pub const KEY_ID_VAR: &'static str = "APCA_API_KEY_ID";
+	pub const SECRET_VAR: &'static str = "APCA_API_SECRET_KEY";
+
+	/// `urls` defaults to paper trading, `retry` to
+	/// [`RetryPolicy::default`].
+	///
+	/// # Errors
+	///
+	/// Fails when the credentials are not valid header values or the HTTP
+	/// client cannot be built.
+	#[builder]
+	pub fn new(
+		#[builder(into)] key_id: String,
+		#[builder(into)] secret: String,
+		#[builder(default)] urls: AlpacaUrls,
+		#[builder(default)] retry: RetryPolicy,
+	) -> AlpacaResult<Self> {
+		let mut secret_header = HeaderValue::from_str(&secret)?;
+		secret_header.set_sensitive(true);
+		let client = Client::builder()
 			.default_headers(
-				[
-					(HeaderName::from_static(HDR_KEY_ID), HeaderValue::from_str(key_id).unwrap()),
-					(HeaderName::from_static(HDR_SECRET), HeaderValue::from_str(secret).unwrap()),
-				]
-				.into_iter()
-				.collect(),
+				[(HeaderName::from_static(HDR_KEY_ID), HeaderValue::from_str(&key_id)?), (HeaderName::from_static(HDR_SECRET), secret_header)].into_iter().collect(),
 			)
-			.build()
-			.expect("COULDNT BUILD CLIENT!!"),
-	)
+			.build()?;
+		Ok(Self { client, urls, key_id, secret, retry })
+	}
+
+	/// Read the credentials from [`Self::KEY_ID_VAR`] and [`Self::SECRET_VAR`],
+	/// and the URLs with [`AlpacaUrls::from_env`].
+	///
+	/// # Errors
+	///
+	/// Fails when a variable is missing or the client cannot be built.
+	pub fn from_env() -> AlpacaResult<Self> {
+		let var = |var| std::env::var(var).map_err(|_| AlpacaError::MissingCredentials { var });
+		Self::builder().key_id(var(Self::KEY_ID_VAR)?).secret(var(Self::SECRET_VAR)?).urls(AlpacaUrls::from_env()).build()
+	}
+
+	pub fn urls(&self) -> &AlpacaUrls {
+		&self.urls
+	}
+
+	pub fn retry(&self) -> &RetryPolicy {
+		&self.retry
+	}
+
+	pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+		self.retry = retry;
+		self
+	}
+
+	pub fn url<R>(&self, route: R) -> String
+	where
+		R: Into<EAlpacaRoute>,
+	{
+		route.into().url_path(&self.urls)
+	}
+
+	/// The `auth` message of the websocket streams.
+	pub(crate) fn authenticate(&self) -> Authenticate<'static> {
+		Authenticate::Request { key_id: self.key_id.clone().into(), secret: self.secret.clone().into() }
+	}
+
+	pub(crate) fn api(&self, method: Method, route: EApiRoute) -> RequestBuilder {
+		self.client.request(method, self.url(route))
+	}
+
+	/// Send `request` and decode the JSON response.
+	pub(crate) async fn send<T>(&self, request: RequestBuilder) -> AlpacaResult<T>
+	where
+		T: DeserializeOwned,
+	{
+		decode(self.retry.send(request).await?).await
+	}
+
+	/// Send `request`, ignoring the body of the response.
+	pub(crate) async fn send_empty(&self, request: RequestBuilder) -> AlpacaResult<()> {
+		self.retry.send(request).await?;
+		Ok(())
+	}
+}
+
+/// Exponential backoff applied to [`AlpacaError::RateLimited`] responses. The
+/// reset time sent by the API takes precedence over the computed delay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, bon::Builder)]
+pub struct RetryPolicy {
+	#[builder(default = 3)]
+	pub max_retries: u32,
+	#[builder(default = Duration::from_secs(1))]
+	pub initial_backoff: Duration,
+	/// Also caps the wait for the reset time.
+	#[builder(default = Duration::from_secs(60))]
+	pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
+
+impl RetryPolicy {
+	pub fn none() -> Self {
+		Self::builder().max_retries(0).build()
+	}
+
+	pub fn backoff(&self, attempt: u32) -> Duration {
+		self.initial_backoff.saturating_mul(2_u32.saturating_pow(attempt)).min(self.max_backoff)
+	}
+
+	/// How long to wait before retrying after `error`, `None` when the request
+	/// should not be retried.
+	pub fn delay(&self, attempt: u32, error: &AlpacaError) -> Option<Duration> {
+		let AlpacaError::RateLimited { reset } = error else {
+			return None;
+		};
+		if attempt >= self.max_retries {
+			return None;
+		}
+		let until_reset = reset.and_then(|reset| (reset - Utc::now()).to_std().ok());
+		Some(until_reset.unwrap_or_else(|| self.backoff(attempt)).min(self.max_backoff))
+	}
+
+	/// Send `request`, retrying it while the API reports rate limits. Fails
+	/// with the error of non-success responses.
+	///
+	/// # Errors
+	///
+	/// Fails when the request cannot be sent, the response is not a success
+	/// or the retries are exhausted.
+	pub async fn send(&self, mut request: RequestBuilder) -> AlpacaResult<Response> {
+		let mut attempt = 0;
+		loop {
+			// Requests with a streamed body cannot be cloned, they are sent once.
+			let retry = request.try_clone();
+			let response = request.send().await?;
+			if response.status().is_success() {
+				return Ok(response);
+			}
+			let error = AlpacaError::from_response(response).await;
+			let (Some(request_again), Some(delay)) = (retry, self.delay(attempt, &error)) else {
+				return Err(error);
+			};
+			tokio::time::sleep(delay).await;
+			request = request_again;
+			attempt += 1;
+		}
+	}
+}
+
+/// Decode the JSON body of `response`.
+pub(crate) async fn decode<T>(response: Response) -> AlpacaResult<T>
+where
+	T: DeserializeOwned,
+{
+	Ok(serde_json::from_slice(&response.bytes().await?)?)
+}
+
+/// A paper trading client.
+///
+/// # Panics
+///
+/// When the credentials are not valid header values.
+pub fn get_alpaca_reqwest_client(key_id: &str, secret: &str) -> AlpacaClient {
+	AlpacaClient::builder().key_id(key_id).secret(secret).build().expect("COULDNT BUILD CLIENT!!")
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		chrono::TimeDelta,
+		tokio::{
+			io::{AsyncReadExt, AsyncWriteExt},
+			net::TcpListener,
+			task::JoinHandle,
+		},
+	};
+
+	fn rate_limited(reset: Option<TimeDelta>) -> AlpacaError {
+		AlpacaError::RateLimited { reset: reset.map(|reset| Utc::now() + reset) }
+	}
+
+	#[test]
+	fn delays_wait_for_the_reset_time() {
+		let retry = RetryPolicy::default();
+		assert_eq!(retry.delay(0, &rate_limited(None)), Some(Duration::from_secs(1)));
+		assert_eq!(retry.delay(2, &rate_limited(None)), Some(Duration::from_secs(4)));
+		let delay = retry.delay(0, &rate_limited(Some(TimeDelta::seconds(10)))).unwrap();
+		assert!(delay > Duration::from_secs(9) && delay <= Duration::from_secs(10), "{delay:?}");
+		assert_eq!(retry.delay(0, &rate_limited(Some(TimeDelta::hours(1)))), Some(retry.max_backoff));
+		assert_eq!(retry.delay(1, &rate_limited(Some(TimeDelta::seconds(-5)))), Some(Duration::from_secs(2)));
+		assert_eq!(retry.delay(3, &rate_limited(None)), None);
+		assert_eq!(retry.delay(0, &AlpacaError::NotFound { message: "gone".to_owned() }), None);
+		assert_eq!(RetryPolicy::none().delay(0, &rate_limited(None)), None);
+	}
+
+	/// Answer one connection per response, in order, and return the URL and
+	/// the number of requests served.
+	async fn serve(responses: Vec<&'static str>) -> (String, JoinHandle<usize>) {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let url = format!("http://{}/v2/test", listener.local_addr().unwrap());
+		let server = tokio::spawn(async move {
+			for response in &responses {
+				let (mut stream, _) = listener.accept().await.unwrap();
+				let mut request = Vec::new();
+				while !request.ends_with(b"\r\n\r\n") {
+					let mut buffer = [0; 1024];
+					let read = stream.read(&mut buffer).await.unwrap();
+					request.extend_from_slice(&buffer[..read]);
+				}
+				stream.write_all(response.as_bytes()).await.unwrap();
+				stream.shutdown().await.unwrap();
+			}
+			responses.len()
+		});
+		(url, server)
+	}
+
+	const TOO_MANY_REQUESTS: &str = "HTTP/1.1 429 Too Many Requests\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+
+	#[tokio::test]
+	async fn rate_limits_are_retried() {
+		let (url, server) = serve(vec![TOO_MANY_REQUESTS, "HTTP/1.1 200 OK\r\ncontent-length: 11\r\nconnection: close\r\n\r\n{\"ok\":true}"]).await;
+		let retry = RetryPolicy::builder().initial_backoff(Duration::from_millis(1)).build();
+		let response = retry.send(Client::new().get(url)).await.unwrap();
+		assert_eq!(decode::<serde_json::Value>(response).await.unwrap(), serde_json::json!({ "ok": true }));
+		assert_eq!(server.await.unwrap(), 2);
+	}
+
+	#[tokio::test]
+	async fn retries_run_out() {
+		let (url, server) = serve(vec![TOO_MANY_REQUESTS, TOO_MANY_REQUESTS]).await;
+		let retry = RetryPolicy::builder().max_retries(1).initial_backoff(Duration::from_millis(1)).build();
+		assert!(matches!(retry.send(Client::new().get(url)).await, Err(AlpacaError::RateLimited { reset: None })));
+		assert_eq!(server.await.unwrap(), 2);
+	}
+
+	#[tokio::test]
+	async fn statuses_map_to_errors() {
+		let (url, server) = serve(vec![
+			"HTTP/1.1 401 Unauthorized\r\ncontent-length: 55\r\nconnection: close\r\n\r\n{\"code\":40110000,\"message\":\"request is not authorized\"}",
+			"HTTP/1.1 422 Unprocessable Entity\r\ncontent-length: 45\r\nconnection: close\r\n\r\n{\"code\":42210000,\"message\":\"qty must be > 0\"}",
+			"HTTP/1.1 500 Internal Server Error\r\ncontent-length: 4\r\nconnection: close\r\n\r\noops",
+		])
+		.await;
+		let retry = RetryPolicy::none();
+		let send = || retry.send(Client::new().get(&url));
+		assert!(matches!(send().await, Err(AlpacaError::Unauthorized { message }) if message == "request is not authorized"));
+		assert!(matches!(send().await, Err(AlpacaError::UnprocessableOrder { code: Some(42_210_000), message }) if message == "qty must be > 0"));
+		assert!(matches!(send().await, Err(AlpacaError::Api { status: 500, code: None, message }) if message == "oops"));
+		assert_eq!(server.await.unwrap(), 3);
+	}
 }
@@ -0,0 +1,69 @@
+use {super::Indicator, crate::data::bars::bars_dtos::NewBar};
+
+/// The average true range, with Wilder's smoothing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Atr {
+	period: usize,
+	previous_close: Option<f64>,
+	/// The true ranges seen, until `period` of them.
+	count: usize,
+	value: f64,
+}
+
+impl Atr {
+	/// # Panics
+	///
+	/// Panics when `period` is zero.
+	pub fn new(period: usize) -> Self {
+		assert!(period > 0, "the period must be positive");
+		Self { period, previous_close: None, count: 0, value: 0.0 }
+	}
+}
+
+impl Indicator for Atr {
+	type Output = f64;
+
+	/// `None` until `period` bars were seen, the first bar's range being its
+	/// high minus its low.
+	#[allow(clippy::cast_precision_loss)]
+	fn update(&mut self, bar: &NewBar) -> Option<f64> {
+		let (high, low) = (f64::from(bar.high), f64::from(bar.low));
+		let range = match self.previous_close.replace(f64::from(bar.close)) {
+			Some(close) => (high - low).max((high - close).abs()).max((low - close).abs()),
+			None => high - low,
+		};
+		let period = self.period as f64;
+		if self.count < self.period {
+			self.count += 1;
+			self.value += range / period;
+			(self.count == self.period).then_some(self.value)
+		} else {
+			self.value = (self.value * (period - 1.0) + range) / period;
+			Some(self.value)
+		}
+	}
+}
+
+pub fn atr(bars: &[NewBar], period: usize) -> Vec<Option<f64>> {
+	Atr::new(period).batch(bars)
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, crate::indicators::bars};
+
+	fn bar(high: f32, low: f32, close: f32) -> NewBar {
+		NewBar { high, low, ..bars(&[close]).remove(0) }
+	}
+
+	#[test]
+	fn the_first_range_is_the_first_bar() {
+		assert_eq!(atr(&[bar(10.0, 8.0, 9.0)], 1), [Some(2.0)]);
+	}
+
+	#[test]
+	fn ranges_include_gaps_from_the_previous_close() {
+		let bars = [bar(10.0, 8.0, 9.0), bar(12.0, 10.0, 11.0), bar(11.0, 10.0, 10.5)];
+		assert_eq!(atr(&bars, 2), [None, Some(2.5), Some(1.75)]);
+	}
+}
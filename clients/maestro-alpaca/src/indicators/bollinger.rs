@@ -0,0 +1,69 @@
+use {
+	super::{Indicator, moving_average::Sma},
+	crate::data::bars::bars_dtos::NewBar,
+};
+
+/// Bollinger bands of the closes, usually over 20 bars at 2 standard
+/// deviations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BollingerBands {
+	average: Sma,
+	deviations: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bands {
+	pub lower: f64,
+	/// The simple moving average.
+	pub middle: f64,
+	pub upper: f64,
+}
+
+impl BollingerBands {
+	/// # Panics
+	///
+	/// Panics when `period` is zero.
+	pub fn new(period: usize, deviations: f64) -> Self {
+		Self { average: Sma::new(period), deviations }
+	}
+
+	/// Add the next value, `None` until `period` values were seen.
+	#[allow(clippy::cast_precision_loss)]
+	pub fn push(&mut self, value: f64) -> Option<Bands> {
+		let middle = self.average.push(value)?;
+		let window = self.average.window();
+		let variance = window.iter().map(|value| (value - middle).powi(2)).sum::<f64>() / window.len() as f64;
+		let width = self.deviations * variance.sqrt();
+		Some(Bands { lower: middle - width, middle, upper: middle + width })
+	}
+}
+
+impl Default for BollingerBands {
+	fn default() -> Self {
+		Self::new(20, 2.0)
+	}
+}
+
+impl Indicator for BollingerBands {
+	type Output = Bands;
+
+	fn update(&mut self, bar: &NewBar) -> Option<Bands> {
+		self.push(f64::from(bar.close))
+	}
+}
+
+pub fn bollinger_bands(bars: &[NewBar], period: usize, deviations: f64) -> Vec<Option<Bands>> {
+	BollingerBands::new(period, deviations).batch(bars)
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, crate::indicators::bars};
+
+	#[test]
+	fn bands_use_the_population_deviation() {
+		let bands = bollinger_bands(&bars(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]), 8, 2.0);
+		assert!(bands[..7].iter().all(Option::is_none));
+		assert_eq!(bands[7], Some(Bands { lower: 1.0, middle: 5.0, upper: 9.0 }));
+	}
+}
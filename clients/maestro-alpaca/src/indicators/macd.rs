@@ -0,0 +1,77 @@
+use {
+	super::{Indicator, moving_average::Ema},
+	crate::data::bars::bars_dtos::NewBar,
+};
+
+/// The moving average convergence divergence of the closes, usually with
+/// periods 12, 26 and 9.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Macd {
+	fast: Ema,
+	slow: Ema,
+	signal: Ema,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MacdValue {
+	/// The fast average minus the slow one.
+	pub macd: f64,
+	/// The average of `macd`.
+	pub signal: f64,
+	/// `macd` minus `signal`.
+	pub histogram: f64,
+}
+
+impl Macd {
+	/// # Panics
+	///
+	/// Panics when a period is zero.
+	pub fn new(fast: usize, slow: usize, signal: usize) -> Self {
+		Self { fast: Ema::new(fast), slow: Ema::new(slow), signal: Ema::new(signal) }
+	}
+
+	/// Add the next value, `None` until the slow and signal averages are
+	/// both ready.
+	pub fn push(&mut self, value: f64) -> Option<MacdValue> {
+		let (fast, slow) = (self.fast.push(value), self.slow.push(value));
+		let macd = fast? - slow?;
+		let signal = self.signal.push(macd)?;
+		Some(MacdValue { macd, signal, histogram: macd - signal })
+	}
+}
+
+impl Default for Macd {
+	fn default() -> Self {
+		Self::new(12, 26, 9)
+	}
+}
+
+impl Indicator for Macd {
+	type Output = MacdValue;
+
+	fn update(&mut self, bar: &NewBar) -> Option<MacdValue> {
+		self.push(f64::from(bar.close))
+	}
+}
+
+pub fn macd(bars: &[NewBar], fast: usize, slow: usize, signal: usize) -> Vec<Option<MacdValue>> {
+	Macd::new(fast, slow, signal).batch(bars)
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, crate::indicators::bars};
+
+	#[test]
+	fn macd_waits_for_the_slow_and_signal_averages() {
+		let closes = (0..10u8).map(f32::from).collect::<Vec<_>>();
+		let values = macd(&bars(&closes), 3, 6, 3);
+		// The slow average is ready at the 6th bar, the signal 2 bars later.
+		assert_eq!(values.iter().position(Option::is_some), Some(7));
+		// On a straight line both averages lag by a constant.
+		let value = values[7].unwrap();
+		assert!((value.macd - 1.5).abs() < 1e-9);
+		assert!((value.signal - 1.5).abs() < 1e-9);
+		assert!(value.histogram.abs() < 1e-9);
+	}
+}
@@ -0,0 +1,47 @@
+//! Technical indicators over bars. Each one updates a bar at a time for live
+//! data, and has a function over a slice of bars built on it.
+
+pub mod atr;
+pub mod bollinger;
+pub mod macd;
+pub mod moving_average;
+pub mod rsi;
+pub mod vwap;
+
+use crate::data::bars::bars_dtos::NewBar;
+
+pub trait Indicator {
+	type Output;
+
+	/// Add the next bar, `None` until enough bars were seen.
+	fn update(&mut self, bar: &NewBar) -> Option<Self::Output>;
+
+	/// The output at each of `bars`, oldest first.
+	fn batch(mut self, bars: &[NewBar]) -> Vec<Option<Self::Output>>
+	where
+		Self: Sized,
+	{
+		bars.iter().map(|bar| self.update(bar)).collect()
+	}
+}
+
+/// Bars a minute apart closing at `closes`, for tests.
+#[cfg(test)]
+fn bars(closes: &[f32]) -> Vec<NewBar> {
+	use chrono::{TimeDelta, TimeZone, Utc};
+
+	let start = Utc.with_ymd_and_hms(2024, 5, 6, 13, 30, 0).unwrap();
+	(0..)
+		.zip(closes)
+		.map(|(minute, &close)| NewBar {
+			time: start + TimeDelta::minutes(minute),
+			open: close,
+			high: close,
+			low: close,
+			close,
+			volume: 100,
+			trade_count: 1,
+			weighted_average: close,
+		})
+		.collect()
+}
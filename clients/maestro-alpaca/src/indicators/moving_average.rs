@@ -0,0 +1,110 @@
+use {super::Indicator, crate::data::bars::bars_dtos::NewBar, std::collections::VecDeque};
+
+/// The simple moving average of the closes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sma {
+	period: usize,
+	window: VecDeque<f64>,
+	sum: f64,
+}
+
+impl Sma {
+	/// # Panics
+	///
+	/// Panics when `period` is zero.
+	pub fn new(period: usize) -> Self {
+		assert!(period > 0, "the period must be positive");
+		Self { period, window: VecDeque::with_capacity(period + 1), sum: 0.0 }
+	}
+
+	/// Add the next value, `None` until `period` values were seen.
+	#[allow(clippy::cast_precision_loss)]
+	pub fn push(&mut self, value: f64) -> Option<f64> {
+		self.window.push_back(value);
+		self.sum += value;
+		if self.window.len() > self.period {
+			self.sum -= self.window.pop_front().unwrap_or_default();
+		}
+		(self.window.len() == self.period).then(|| self.sum / self.period as f64)
+	}
+
+	/// The last `period` values, oldest first.
+	pub fn window(&self) -> &VecDeque<f64> {
+		&self.window
+	}
+}
+
+impl Indicator for Sma {
+	type Output = f64;
+
+	fn update(&mut self, bar: &NewBar) -> Option<f64> {
+		self.push(f64::from(bar.close))
+	}
+}
+
+/// The exponential moving average of the closes, starting from the simple
+/// average of the first `period` ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ema {
+	alpha: f64,
+	seed: Sma,
+	value: Option<f64>,
+}
+
+impl Ema {
+	/// # Panics
+	///
+	/// Panics when `period` is zero.
+	#[allow(clippy::cast_precision_loss)]
+	pub fn new(period: usize) -> Self {
+		Self { alpha: 2.0 / (period as f64 + 1.0), seed: Sma::new(period), value: None }
+	}
+
+	/// Add the next value, `None` until `period` values were seen.
+	pub fn push(&mut self, value: f64) -> Option<f64> {
+		self.value = match self.value {
+			Some(previous) => Some(previous + self.alpha * (value - previous)),
+			None => self.seed.push(value),
+		};
+		self.value
+	}
+}
+
+impl Indicator for Ema {
+	type Output = f64;
+
+	fn update(&mut self, bar: &NewBar) -> Option<f64> {
+		self.push(f64::from(bar.close))
+	}
+}
+
+pub fn sma(bars: &[NewBar], period: usize) -> Vec<Option<f64>> {
+	Sma::new(period).batch(bars)
+}
+
+pub fn ema(bars: &[NewBar], period: usize) -> Vec<Option<f64>> {
+	Ema::new(period).batch(bars)
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, crate::indicators::bars};
+
+	#[test]
+	fn sma_averages_the_last_values() {
+		let averages = sma(&bars(&[2.0, 4.0, 6.0, 8.0, 12.0]), 3);
+		assert_eq!(averages[..4], [None, None, Some(4.0), Some(6.0)]);
+		assert!((averages[4].unwrap() - 26.0 / 3.0).abs() < 1e-9);
+	}
+
+	#[test]
+	fn ema_is_seeded_with_the_sma() {
+		assert_eq!(ema(&bars(&[2.0, 4.0, 6.0, 8.0, 12.0]), 3), [None, None, Some(4.0), Some(6.0), Some(9.0)]);
+	}
+
+	#[test]
+	#[should_panic(expected = "the period must be positive")]
+	fn periods_must_be_positive() {
+		Sma::new(0);
+	}
+}
@@ -0,0 +1,77 @@
+use {super::Indicator, crate::data::bars::bars_dtos::NewBar};
+
+/// The relative strength index of the closes, from 0 to 100, with Wilder's
+/// smoothing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rsi {
+	period: usize,
+	previous: Option<f64>,
+	/// The changes seen, until `period` of them.
+	count: usize,
+	gain: f64,
+	loss: f64,
+}
+
+impl Rsi {
+	/// # Panics
+	///
+	/// Panics when `period` is zero.
+	pub fn new(period: usize) -> Self {
+		assert!(period > 0, "the period must be positive");
+		Self { period, previous: None, count: 0, gain: 0.0, loss: 0.0 }
+	}
+
+	/// Add the next value, `None` until `period` changes were seen.
+	#[allow(clippy::cast_precision_loss)]
+	pub fn push(&mut self, value: f64) -> Option<f64> {
+		let change = value - self.previous.replace(value)?;
+		let (gain, loss) = (change.max(0.0), (-change).max(0.0));
+		let period = self.period as f64;
+		if self.count < self.period {
+			// The first averages are simple ones.
+			self.count += 1;
+			self.gain += gain / period;
+			self.loss += loss / period;
+			if self.count < self.period {
+				return None;
+			}
+		} else {
+			self.gain = (self.gain * (period - 1.0) + gain) / period;
+			self.loss = (self.loss * (period - 1.0) + loss) / period;
+		}
+		Some(if self.loss == 0.0 { 100.0 } else { 100.0 - 100.0 / (1.0 + self.gain / self.loss) })
+	}
+}
+
+impl Indicator for Rsi {
+	type Output = f64;
+
+	fn update(&mut self, bar: &NewBar) -> Option<f64> {
+		self.push(f64::from(bar.close))
+	}
+}
+
+pub fn rsi(bars: &[NewBar], period: usize) -> Vec<Option<f64>> {
+	Rsi::new(period).batch(bars)
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, crate::indicators::bars};
+
+	#[test]
+	fn rsi_uses_wilder_smoothing() {
+		let closes = [44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08, 45.89, 46.03, 45.61, 46.28, 46.28, 46.00, 46.03, 46.41, 46.22, 45.64];
+		let values = rsi(&bars(&closes), 14);
+		assert!(values[..14].iter().all(Option::is_none));
+		let expected = [70.46, 66.25, 66.48, 69.35, 66.29, 57.92];
+		for (value, expected) in values[14..].iter().zip(expected) {
+			assert!((value.unwrap() - expected).abs() < 0.01, "{value:?} is not {expected}");
+		}
+	}
+
+	#[test]
+	fn rsi_is_100_without_losses() {
+		assert_eq!(rsi(&bars(&[1.0, 2.0, 3.0, 4.0]), 3), [None, None, None, Some(100.0)]);
+	}
+}
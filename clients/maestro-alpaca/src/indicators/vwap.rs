@@ -0,0 +1,57 @@
+use {super::Indicator, crate::data::bars::bars_dtos::NewBar, chrono::NaiveDate};
+
+/// The volume weighted average price since the start of the day, from the
+/// average price of each bar. Days start at midnight UTC, which falls
+/// outside of regular US hours.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Vwap {
+	day: Option<NaiveDate>,
+	value: f64,
+	volume: f64,
+}
+
+impl Vwap {
+	pub fn new() -> Self {
+		Self::default()
+	}
+}
+
+impl Indicator for Vwap {
+	type Output = f64;
+
+	/// `None` until some volume was traded in the day.
+	fn update(&mut self, bar: &NewBar) -> Option<f64> {
+		let day = bar.time.date_naive();
+		if self.day.replace(day) != Some(day) {
+			self.value = 0.0;
+			self.volume = 0.0;
+		}
+		let volume = f64::from(bar.volume);
+		self.value += f64::from(bar.weighted_average) * volume;
+		self.volume += volume;
+		(self.volume > 0.0).then(|| self.value / self.volume)
+	}
+}
+
+pub fn vwap(bars: &[NewBar]) -> Vec<Option<f64>> {
+	Vwap::new().batch(bars)
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		chrono::{TimeDelta, TimeZone, Utc},
+	};
+
+	fn bar(day: u32, weighted_average: f32, volume: i32) -> NewBar {
+		let time = Utc.with_ymd_and_hms(2024, 5, day, 14, 0, 0).unwrap() + TimeDelta::minutes(volume.into());
+		NewBar { time, open: 0.0, high: 0.0, low: 0.0, close: 0.0, volume, trade_count: 1, weighted_average }
+	}
+
+	#[test]
+	fn vwap_resets_every_day() {
+		let bars = [bar(6, 5.0, 0), bar(6, 10.0, 100), bar(6, 20.0, 300), bar(7, 30.0, 50)];
+		assert_eq!(vwap(&bars), [None, Some(10.0), Some(17.5), Some(30.0)]);
+	}
+}
@@ -1,7 +1,24 @@
+pub mod alpaca_env;
+pub mod api;
+pub mod backtest;
 pub mod data;
+pub mod indicators;
+pub mod routes;
+
+#[cfg(feature = "server")]
+pub mod broker;
+
+#[cfg(feature = "server")]
+pub mod db;
+
+#[cfg(feature = "server")]
+pub mod error;
 
 #[cfg(feature = "server")]
 pub mod get_client;
 
+#[cfg(feature = "server")]
+pub mod websocket;
+
 #[cfg(all(feature = "server", feature = "dioxus"))]
 pub mod server_ctx;
@@ -0,0 +1,139 @@
+use {
+	crate::{
+		alpaca_env::AlpacaUrls,
+		data::enums::{crypto_feed::CryptoFeed, feed::Feed},
+	},
+	uuid::Uuid,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EAlpacaRoute {
+	Api(EApiRoute),
+	Data(EDataRoute),
+	Stream(EStreamRoute),
+}
+
+/// Routes of the trading API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EApiRoute {
+	Account,
+	AccountConfigurations,
+	AccountActivities,
+	Assets,
+	/// By symbol or asset ID.
+	Asset(String),
+	Calendar,
+	Clock,
+	Orders,
+	Order(Uuid),
+	Positions,
+	/// By symbol or asset ID.
+	Position(String),
+	Watchlists,
+	Watchlist(Uuid),
+	WatchlistSymbol(Uuid, String),
+}
+
+/// Routes of the market data API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EDataRoute {
+	Bars(String),
+	BarsMulti,
+	LatestQuotes,
+	Quotes(String),
+	Trades(String),
+	CryptoBars(CryptoFeed),
+	CryptoQuotes(CryptoFeed),
+	CryptoTrades(CryptoFeed),
+	CryptoLatestOrderbooks(CryptoFeed),
+	/// Snapshots of the contracts of an underlying symbol.
+	OptionChain(String),
+	OptionSnapshots,
+}
+
+/// Websocket streams.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EStreamRoute {
+	/// Real-time stock trades, quotes and bars.
+	MarketData(Feed),
+	/// Updates of the account's orders, on the trading API host.
+	TradeUpdates,
+}
+
+impl EApiRoute {
+	pub fn path(&self) -> String {
+		match self {
+			EApiRoute::Account => "/v2/account".to_string(),
+			EApiRoute::AccountConfigurations => "/v2/account/configurations".to_string(),
+			EApiRoute::AccountActivities => "/v2/account/activities".to_string(),
+			EApiRoute::Assets => "/v2/assets".to_string(),
+			EApiRoute::Asset(symbol) => format!("/v2/assets/{symbol}"),
+			EApiRoute::Calendar => "/v2/calendar".to_string(),
+			EApiRoute::Clock => "/v2/clock".to_string(),
+			EApiRoute::Orders => "/v2/orders".to_string(),
+			EApiRoute::Order(id) => format!("/v2/orders/{id}"),
+			EApiRoute::Positions => "/v2/positions".to_string(),
+			EApiRoute::Position(symbol) => format!("/v2/positions/{symbol}"),
+			EApiRoute::Watchlists => "/v2/watchlists".to_string(),
+			EApiRoute::Watchlist(id) => format!("/v2/watchlists/{id}"),
+			EApiRoute::WatchlistSymbol(id, symbol) => format!("/v2/watchlists/{id}/{symbol}"),
+		}
+	}
+}
+
+impl EDataRoute {
+	pub fn path(&self) -> String {
+		match self {
+			EDataRoute::Bars(symbol) => format!("/v2/stocks/{symbol}/bars"),
+			EDataRoute::BarsMulti => "/v2/stocks/bars".to_string(),
+			EDataRoute::LatestQuotes => "/v2/stocks/quotes/latest".to_string(),
+			EDataRoute::Quotes(symbol) => format!("/v2/stocks/{symbol}/quotes"),
+			EDataRoute::Trades(symbol) => format!("/v2/stocks/{symbol}/trades"),
+			EDataRoute::CryptoBars(feed) => format!("/v1beta3/crypto/{}/bars", feed.path()),
+			EDataRoute::CryptoQuotes(feed) => format!("/v1beta3/crypto/{}/quotes", feed.path()),
+			EDataRoute::CryptoTrades(feed) => format!("/v1beta3/crypto/{}/trades", feed.path()),
+			EDataRoute::CryptoLatestOrderbooks(feed) => format!("/v1beta3/crypto/{}/latest/orderbooks", feed.path()),
+			EDataRoute::OptionChain(underlying) => format!("/v1beta1/options/snapshots/{underlying}"),
+			EDataRoute::OptionSnapshots => "/v1beta1/options/snapshots".to_string(),
+		}
+	}
+}
+
+impl EStreamRoute {
+	pub fn path(&self) -> String {
+		match self {
+			EStreamRoute::MarketData(Feed::Iex) => "/v2/iex".to_string(),
+			EStreamRoute::MarketData(Feed::Sip) => "/v2/sip".to_string(),
+			EStreamRoute::TradeUpdates => "/stream".to_string(),
+		}
+	}
+}
+
+impl EAlpacaRoute {
+	pub fn url_path(&self, urls: &AlpacaUrls) -> String {
+		match self {
+			EAlpacaRoute::Api(route) => format!("{}{}", urls.api, route.path()),
+			EAlpacaRoute::Data(route) => format!("{}{}", urls.data, route.path()),
+			EAlpacaRoute::Stream(route @ EStreamRoute::TradeUpdates) => format!("{}{}", urls.api.replacen("http", "ws", 1), route.path()),
+			EAlpacaRoute::Stream(route) => format!("{}{}", urls.stream, route.path()),
+		}
+	}
+}
+
+impl From<EApiRoute> for EAlpacaRoute {
+	fn from(route: EApiRoute) -> Self {
+		Self::Api(route)
+	}
+}
+
+impl From<EDataRoute> for EAlpacaRoute {
+	fn from(route: EDataRoute) -> Self {
+		Self::Data(route)
+	}
+}
+
+impl From<EStreamRoute> for EAlpacaRoute {
+	fn from(route: EStreamRoute) -> Self {
+		Self::Stream(route)
+	}
+}
@@ -1,6 +1,10 @@
 use {crate::get_client::AlpacaClient, dioxus::prelude::*, reqwest::Client, std::ops::Deref};
 
-pub async fn alpaca_client_from_ctx() -> Result<Client, ServerFnError> {
+pub async fn alpaca_from_ctx() -> Result<AlpacaClient, ServerFnError> {
 	let FromContext(alpaca_client): FromContext<AlpacaClient> = extract().await?;
-	Ok(alpaca_client.deref().clone())
+	Ok(alpaca_client)
+}
+
+pub async fn alpaca_client_from_ctx() -> Result<Client, ServerFnError> {
+	Ok(alpaca_from_ctx().await?.deref().clone())
 }
@@ -0,0 +1,71 @@
+use {
+	crate::error::{AlpacaError, AlpacaResult},
+	futures::{SinkExt, StreamExt},
+	serde::Serialize,
+	std::time::Duration,
+	tokio::net::TcpStream,
+	tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message},
+};
+
+pub(crate) type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// How a stream reconnects after losing its connection. The delay doubles
+/// after each failed attempt, up to `max_backoff`, and is reset once the
+/// stream is authenticated again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, bon::Builder)]
+pub struct ReconnectPolicy {
+	#[builder(default = Duration::from_secs(1))]
+	pub initial_backoff: Duration,
+	#[builder(default = Duration::from_secs(30))]
+	pub max_backoff: Duration,
+	/// Consecutive attempts before giving up, `None` retries forever.
+	pub max_attempts: Option<u32>,
+}
+
+impl ReconnectPolicy {
+	/// Fail on the first disconnection.
+	pub fn never() -> Self {
+		Self::builder().max_attempts(0).build()
+	}
+
+	/// The delay before the `attempt`th reconnection, counted from 0, or
+	/// `None` when attempts are exhausted.
+	pub fn delay(&self, attempt: u32) -> Option<Duration> {
+		if self.max_attempts.is_some_and(|max| attempt >= max) {
+			return None;
+		}
+		Some(self.initial_backoff.saturating_mul(2_u32.saturating_pow(attempt)).min(self.max_backoff))
+	}
+}
+
+impl Default for ReconnectPolicy {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
+
+pub(crate) async fn connect(url: &str) -> AlpacaResult<Socket> {
+	let (socket, _) = tokio_tungstenite::connect_async(url).await?;
+	Ok(socket)
+}
+
+pub(crate) async fn send_json<T>(socket: &mut Socket, message: &T) -> AlpacaResult<()>
+where
+	T: Serialize,
+{
+	socket.send(Message::text(serde_json::to_string(message)?)).await?;
+	Ok(())
+}
+
+/// The next text or binary frame. Pings are answered by the socket, a close
+/// frame or the end of the connection is [`AlpacaError::Disconnected`].
+pub(crate) async fn next_frame(socket: &mut Socket) -> AlpacaResult<Vec<u8>> {
+	loop {
+		match socket.next().await.ok_or(AlpacaError::Disconnected)?? {
+			Message::Text(text) => return Ok(text.as_bytes().to_vec()),
+			Message::Binary(bytes) => return Ok(bytes.to_vec()),
+			Message::Close(_) => return Err(AlpacaError::Disconnected),
+			Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => {},
+		}
+	}
+}
@@ -0,0 +1,239 @@
+use {
+	crate::{
+		AnthropicClient, Prompt, Tool, TypedTool,
+		chat_message::{Block, ChatMessage, Content, ToolBlock, Use},
+		client::{AnthropicClientError, AnthropicResult},
+		response::{ResponseMessage, StopReason},
+		stream::FilterExt,
+		tool::InputSchemaError,
+	},
+	futures::future::{BoxFuture, join_all},
+	std::{collections::BTreeMap, num::NonZeroU16, sync::Arc},
+};
+
+/// An async function the [`Agent`] runs when the model requests a tool. The
+/// error message of a failed call is sent back to the model with `is_error`
+/// set so it can recover.
+pub trait ToolHandler: Send + Sync {
+	fn call(&self, input: serde_json::Value) -> BoxFuture<'static, anyhow::Result<Content>>;
+}
+
+impl<F, Fut, C> ToolHandler for F
+where
+	F: Fn(serde_json::Value) -> Fut + Send + Sync,
+	Fut: Future<Output = anyhow::Result<C>> + Send + 'static,
+	C: Into<Content>,
+{
+	fn call(&self, input: serde_json::Value) -> BoxFuture<'static, anyhow::Result<Content>> {
+		let call = self(input);
+		Box::pin(async move { call.await.map(Into::into) })
+	}
+}
+
+/// Tools and their handlers, keyed by [`Tool::name`].
+#[derive(Clone, Default)]
+pub struct ToolRegistry {
+	handlers: BTreeMap<String, (Tool, Arc<dyn ToolHandler>)>,
+}
+
+impl ToolRegistry {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	#[must_use]
+	pub fn register<H>(mut self, tool: Tool, handler: H) -> Self
+	where
+		H: ToolHandler + 'static,
+	{
+		self.insert(tool, handler);
+		self
+	}
+
+	/// Register a handler receiving the decoded input of a [`TypedTool`]. Inputs
+	/// that fail to decode are reported back to the model without calling
+	/// `handler`.
+	///
+	/// # Errors
+	///
+	/// Fails when the input schema of `T` is not an object, see
+	/// [`Tool::check_input_schema`].
+	pub fn register_typed<T, F, Fut, C>(self, handler: F) -> Result<Self, InputSchemaError>
+	where
+		T: TypedTool + 'static,
+		F: Fn(T) -> Fut + Send + Sync + 'static,
+		Fut: Future<Output = anyhow::Result<C>> + Send + 'static,
+		C: Into<Content>,
+	{
+		let tool = T::tool();
+		tool.check_input_schema()?;
+		let name = tool.name.clone();
+		Ok(self.register(tool, move |input: serde_json::Value| {
+			let call = T::decode(&Use { id: String::new(), name: name.clone(), input }).map(&handler);
+			async move { call?.await.map(Into::<Content>::into) }
+		}))
+	}
+
+	pub fn insert<H>(&mut self, tool: Tool, handler: H)
+	where
+		H: ToolHandler + 'static,
+	{
+		self.handlers.insert(tool.name.clone(), (tool, Arc::new(handler)));
+	}
+
+	pub fn tools(&self) -> impl Iterator<Item = &Tool> {
+		self.handlers.values().map(|(tool, _)| tool)
+	}
+
+	/// Run the handler registered for `call` and wrap its outcome in a
+	/// [`ToolBlock`]. Unknown tools are reported to the model as errors.
+	pub async fn execute(&self, call: &Use) -> ToolBlock {
+		let result = match self.handlers.get(&call.name) {
+			Some((_, handler)) => handler.call(call.input.clone()).await,
+			None => Err(anyhow::anyhow!("Unknown tool `{}`", call.name)),
+		};
+		match result {
+			Ok(content) => ToolBlock { tool_use_id: call.id.clone(), content, is_error: false },
+			Err(error) => ToolBlock { tool_use_id: call.id.clone(), content: Content::text(format!("{error:#}")), is_error: true },
+		}
+	}
+}
+
+/// Runs a prompt, executes every [`Block::ToolUse`] the model returns with the
+/// matching handler from its [`ToolRegistry`] and sends the results back until
+/// the model stops asking for tools.
+#[derive(Clone, bon::Builder)]
+pub struct Agent {
+	client: AnthropicClient,
+	#[builder(default)]
+	tools: ToolRegistry,
+	#[builder(default = NonZeroU16::new(10).unwrap())]
+	max_iterations: NonZeroU16,
+}
+
+/// The outcome of an [`Agent`] run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentRun {
+	/// The whole conversation, including the original prompt messages, every
+	/// assistant turn and the tool results.
+	pub messages: Vec<ChatMessage>,
+	/// The last response, the one that ended the run.
+	pub response: ResponseMessage,
+	pub iterations: u16,
+}
+
+impl Agent {
+	/// Run the loop using [`AnthropicClient::chat`].
+	///
+	/// # Errors
+	///
+	/// Fails on any client error or when `max_iterations` is reached while the
+	/// model still requests tools.
+	pub async fn run(&self, prompt: Prompt) -> AnthropicResult<AgentRun> {
+		self.run_with(prompt, false).await
+	}
+
+	/// Run the loop using [`AnthropicClient::stream_chat`], folding every
+	/// stream into a [`ResponseMessage`] before executing tools.
+	///
+	/// # Errors
+	///
+	/// Same as [`Self::run`], plus stream and delta errors.
+	pub async fn run_stream(&self, prompt: Prompt) -> AnthropicResult<AgentRun> {
+		self.run_with(prompt, true).await
+	}
+
+	async fn run_with(&self, mut prompt: Prompt, stream: bool) -> AnthropicResult<AgentRun> {
+		for tool in self.tools.tools() {
+			if !prompt.tools.iter().any(|existing| existing.name == tool.name) {
+				prompt.tools.push(tool.clone());
+			}
+		}
+
+		for iteration in 1..=self.max_iterations.get() {
+			let response = if stream {
+				self.client.stream_chat(prompt.clone()).await?.filter_rate_limit().final_message().await?
+			} else {
+				self.client.chat(prompt.clone()).await?
+			};
+			prompt.messages.push(response.message.clone());
+
+			if !matches!(response.stop_reason, Some(StopReason::ToolUse)) {
+				return Ok(AgentRun { messages: prompt.messages, response, iterations: iteration });
+			}
+
+			let results = join_all(response.message.content.blocks().filter_map(Block::tool_use).map(|call| self.tools.execute(call))).await;
+			prompt.messages.push(ChatMessage::user(results.into_iter().map(Block::from).collect::<Vec<_>>()));
+			// A forced tool choice would keep the model calling tools forever.
+			prompt.tool_choice = None;
+		}
+
+		Err(AnthropicClientError::MaxIterations { iterations: self.max_iterations })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		crate::{Model, chat_message::Usage, tool::Choice, transport::MockTransport},
+	};
+
+	fn response(content: Vec<Block>, stop_reason: StopReason) -> ResponseMessage {
+		ResponseMessage {
+			id: "msg_1".to_owned(),
+			message: ChatMessage::assistant(content),
+			model: Model::default(),
+			stop_reason: Some(stop_reason),
+			stop_sequence: None,
+			usage: Usage::default(),
+		}
+	}
+
+	#[tokio::test]
+	async fn forced_tool_choices_only_apply_to_the_first_turn() {
+		let call = Use { id: "toolu_1".to_owned(), name: "clock".to_owned(), input: serde_json::json!({}) };
+		let mock = Arc::new(
+			MockTransport::new()
+				.message(&response(vec![Block::ToolUse { call, cache_control: None }], StopReason::ToolUse))
+				.message(&response(vec![Block::text("It is noon.")], StopReason::EndTurn)),
+		);
+		let tool = Tool::builder().name("clock".to_owned()).description("The time".to_owned()).input_schema(serde_json::json!({ "type": "object" })).build();
+		let tools = ToolRegistry::new().register(tool, |_| async { anyhow::Ok("noon") });
+		let agent = Agent::builder().client(AnthropicClient::with_transport(mock.clone())).tools(tools).build();
+		let mut prompt = Prompt::builder().messages(vec![ChatMessage::user("What time is it?")]).build(false);
+		prompt.tool_choice = Some(Choice::Any);
+
+		let run = agent.run(prompt).await.unwrap();
+		assert_eq!(run.iterations, 2);
+		assert_eq!(run.messages.len(), 4);
+		let requests = mock.requests().await;
+		let choices = requests.iter().map(|request| request.body.as_ref().and_then(|body| body.get("tool_choice")).cloned()).collect::<Vec<_>>();
+		assert_eq!(choices, [Some(serde_json::json!({ "type": "any" })), None]);
+	}
+
+	/// The city to look up.
+	#[derive(serde::Deserialize, schemars::JsonSchema)]
+	struct Weather {
+		city: String,
+	}
+
+	#[tokio::test]
+	async fn typed_tools_decode_their_input() {
+		let tools = ToolRegistry::new().register_typed(|weather: Weather| async move { anyhow::Ok(format!("Sunny in {}", weather.city)) }).unwrap();
+		let call = Use { id: "toolu_1".to_owned(), name: "Weather".to_owned(), input: serde_json::json!({ "city": "Paris" }) };
+		let block = tools.execute(&call).await;
+		assert!(!block.is_error);
+		assert_eq!(block.content, Content::text("Sunny in Paris"));
+
+		let call = Use { input: serde_json::json!({ "town": "Paris" }), ..call };
+		assert!(tools.execute(&call).await.is_error);
+	}
+
+	#[test]
+	fn typed_tools_need_an_object_schema() {
+		let error = ToolRegistry::new().register_typed(|_: String| async { anyhow::Ok("") }).err().unwrap();
+		assert_eq!(error.tool, "string");
+		assert!(ToolRegistry::new().register_typed(|_: Vec<u8>| async { anyhow::Ok("") }).is_err());
+	}
+}
@@ -0,0 +1,240 @@
+use {
+	crate::{
+		AnthropicClient, Prompt,
+		client::{AnthropicError, AnthropicResult},
+		response::ResponseMessage,
+		transport::{HttpMethod, HttpRequest},
+	},
+	chrono::{DateTime, Utc},
+	futures::{StreamExt, TryStreamExt},
+	serde::{Deserialize, Serialize},
+};
+
+/// A [`Prompt`] in a batch. `custom_id` matches the request with its
+/// [`BatchResult`] and must be unique within the batch, 1 to 64 characters
+/// among letters, digits, `-` and `_`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchRequest {
+	pub custom_id: String,
+	pub params: Prompt,
+}
+
+impl BatchRequest {
+	/// Batches cannot stream, the `stream` flag of `prompt` is overridden.
+	pub fn new<I>(custom_id: I, mut prompt: Prompt) -> Self
+	where
+		I: Into<String>,
+	{
+		prompt.stream = false;
+		Self { custom_id: custom_id.into(), params: prompt }
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum_macros::Display)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessingStatus {
+	InProgress,
+	Canceling,
+	Ended,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestCounts {
+	pub processing: u32,
+	pub succeeded: u32,
+	pub errored: u32,
+	pub canceled: u32,
+	pub expired: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageBatch {
+	pub id: String,
+	pub processing_status: ProcessingStatus,
+	pub request_counts: RequestCounts,
+	pub created_at: DateTime<Utc>,
+	pub expires_at: DateTime<Utc>,
+	pub ended_at: Option<DateTime<Utc>>,
+	pub cancel_initiated_at: Option<DateTime<Utc>>,
+	pub archived_at: Option<DateTime<Utc>>,
+	/// Only set once processing has ended.
+	pub results_url: Option<String>,
+}
+
+impl MessageBatch {
+	pub fn is_ended(&self) -> bool {
+		self.processing_status == ProcessingStatus::Ended
+	}
+}
+
+/// Cursor parameters of [`AnthropicClient::list_batches`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, bon::Builder)]
+pub struct BatchPage {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub limit: Option<u16>,
+	#[builder(into)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub before_id: Option<String>,
+	#[builder(into)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub after_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchList {
+	pub data: Vec<MessageBatch>,
+	pub has_more: bool,
+	pub first_id: Option<String>,
+	pub last_id: Option<String>,
+}
+
+/// A line of the results file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchResult {
+	pub custom_id: String,
+	pub result: BatchOutcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, derive_more::IsVariant)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum BatchOutcome {
+	Succeeded { message: ResponseMessage },
+	Errored { error: BatchError },
+	Canceled,
+	Expired,
+}
+
+impl BatchOutcome {
+	pub fn message(&self) -> Option<&ResponseMessage> {
+		match self {
+			Self::Succeeded { message } => Some(message),
+			_ => None,
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchError {
+	pub error: AnthropicError,
+}
+
+#[derive(Serialize)]
+struct CreateBatchRequest {
+	requests: Vec<BatchRequest>,
+}
+
+impl AnthropicClient {
+	pub const BATCHES_PATH: &'static str = "/v1/messages/batches";
+
+	/// Submit `requests` for asynchronous processing.
+	///
+	/// # Errors
+	///
+	/// Fails when a prompt is invalid, see [`Prompt::validate`], on HTTP
+	/// errors or when the API rejects the batch.
+	pub async fn create_batch<R>(&self, requests: R) -> AnthropicResult<MessageBatch>
+	where
+		R: IntoIterator<Item = BatchRequest>,
+	{
+		let body = CreateBatchRequest { requests: requests.into_iter().collect() };
+		for request in &body.requests {
+			request.params.validate()?;
+		}
+		self.execute_json(HttpRequest::post(Self::BATCHES_PATH, &body)?).await
+	}
+
+	/// List batches, most recent first.
+	///
+	/// # Errors
+	///
+	/// Fails on HTTP or API errors.
+	pub async fn list_batches(&self, page: &BatchPage) -> AnthropicResult<BatchList> {
+		self.execute_json(HttpRequest::get(Self::BATCHES_PATH).query(page)?).await
+	}
+
+	/// # Errors
+	///
+	/// Fails on HTTP or API errors, [`AnthropicError::NotFound`] for unknown ids.
+	pub async fn retrieve_batch(&self, id: &str) -> AnthropicResult<MessageBatch> {
+		self.execute_json(HttpRequest::get(format!("{}/{id}", Self::BATCHES_PATH))).await
+	}
+
+	/// Request the cancellation of a batch. Requests already being processed
+	/// still complete, the batch is [`ProcessingStatus::Canceling`] until then.
+	///
+	/// # Errors
+	///
+	/// Fails on HTTP or API errors.
+	pub async fn cancel_batch(&self, id: &str) -> AnthropicResult<MessageBatch> {
+		self.execute_json(HttpRequest::new(HttpMethod::Post, format!("{}/{id}/cancel", Self::BATCHES_PATH))).await
+	}
+
+	/// Stream the results of an ended batch line by line. Results are not in
+	/// the order of the requests, match them with [`BatchResult::custom_id`].
+	///
+	/// # Errors
+	///
+	/// Fails when the results are not available yet. Every item fails on its
+	/// own on HTTP or parse errors.
+	pub async fn batch_results(&self, id: &str) -> AnthropicResult<impl futures::Stream<Item = AnthropicResult<BatchResult>> + Send + use<>> {
+		let response = self.execute(HttpRequest::get(format!("{}/{id}/results", Self::BATCHES_PATH))).await?;
+		let lines = futures::stream::unfold((response.body, Vec::<u8>::new(), false), |(mut bytes, mut buffer, mut done)| async move {
+			loop {
+				if let Some(end) = buffer.iter().position(|&byte| byte == b'\n') {
+					let line = buffer.drain(..=end).collect::<Vec<_>>();
+					return Some((Ok(line), (bytes, buffer, done)));
+				}
+				if done {
+					return (!buffer.is_empty()).then(|| (Ok(std::mem::take(&mut buffer)), (bytes, buffer, done)));
+				}
+				match bytes.next().await {
+					Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+					Some(Err(error)) => {
+						buffer.clear();
+						return Some((Err(error.into()), (bytes, buffer, true)));
+					},
+					None => done = true,
+				}
+			}
+		});
+		Ok(lines.try_filter(|line| std::future::ready(!line.trim_ascii().is_empty())).and_then(|line| async move { Ok(serde_json::from_slice(&line)?) }))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		crate::{Model, chat_message::ChatMessage, response::StopReason, transport::MockTransport},
+		std::sync::Arc,
+	};
+
+	#[tokio::test]
+	async fn results_are_split_into_lines() {
+		let message = ResponseMessage {
+			id: "msg_1".to_owned(),
+			message: ChatMessage::assistant("Hello!"),
+			model: Model::default(),
+			stop_reason: Some(StopReason::EndTurn),
+			stop_sequence: None,
+			usage: crate::chat_message::Usage::default(),
+		};
+		let results = [
+			BatchResult { custom_id: "first".to_owned(), result: BatchOutcome::Canceled },
+			BatchResult { custom_id: "second".to_owned(), result: BatchOutcome::Expired },
+			BatchResult { custom_id: "third".to_owned(), result: BatchOutcome::Succeeded { message } },
+		];
+		let lines = results.iter().map(|result| serde_json::to_string(result).unwrap()).collect::<Vec<_>>();
+		// Blank lines between the results and no newline after the last one.
+		let body = format!("{}\n\n{}\n  \r\n{}", lines[0], lines[1], lines[2]);
+		let (start, rest) = body.split_at(10);
+		let (middle, end) = rest.split_at(lines[0].len() + 4);
+		let mock = Arc::new(MockTransport::new().chunks(200, [start.to_owned(), middle.to_owned(), end.to_owned()]));
+
+		let client = AnthropicClient::with_transport(mock.clone());
+		let stream = client.batch_results("msgbatch_1").await.unwrap();
+		let received = stream.try_collect::<Vec<_>>().await.unwrap();
+		assert_eq!(received, results);
+		assert_eq!(mock.requests().await[0].path, "/v1/messages/batches/msgbatch_1/results");
+	}
+}
@@ -0,0 +1,104 @@
+use {
+	super::UseChat,
+	crate::chat_message::ChatMessage,
+	dioxus::prelude::*,
+	maestro_headless::{button::Button, textarea::Textarea},
+};
+
+#[derive(Clone, PartialEq, Props)]
+pub struct ChatTranscriptProps {
+	pub chat: UseChat,
+	/// Renders one message. Defaults to a `div` with the text of the message
+	/// and a `data-role` attribute.
+	#[props(optional)]
+	pub render_message: Option<Callback<ChatMessage, Element>>,
+
+	#[props(extends = div, extends = GlobalAttributes)]
+	pub attributes: Vec<Attribute>,
+	#[props(default = Vec::new())]
+	pub extra_attributes: Vec<Attribute>,
+}
+
+/// The messages of a [`UseChat`], announced to screen readers as they stream.
+/// The last error is rendered with `role="alert"`.
+#[component]
+pub fn ChatTranscript(props: ChatTranscriptProps) -> Element {
+	let ChatTranscriptProps { chat, render_message, attributes, extra_attributes } = props;
+	let loading = chat.loading;
+	let error = chat.error;
+
+	rsx! {
+		div {
+			role: "log",
+			aria_live: "polite",
+			aria_busy: loading(),
+			"data-loading": loading().then_some(Some(true)),
+			..attributes,
+			..extra_attributes,
+			for (index , message) in chat.messages.read().iter().cloned().enumerate() {
+				if let Some(render_message) = render_message {
+					Fragment { key: "{index}", {render_message.call(message)} }
+				} else {
+					div { key: "{index}", "data-role": message.role.as_ref(), {message.content.plain_text()} }
+				}
+			}
+			if let Some(error) = error() {
+				div { role: "alert", "data-error": true, "{error}" }
+			}
+		}
+	}
+}
+
+#[derive(Clone, PartialEq, Props)]
+pub struct ChatComposerProps {
+	pub chat: UseChat,
+	#[props(optional, default = String::from("Send"))]
+	pub send_label: String,
+	#[props(optional, default = String::from("Stop"))]
+	pub stop_label: String,
+	#[props(optional, default = String::new())]
+	pub placeholder: String,
+	#[props(default = ReadOnlySignal::new(Signal::new(false)))]
+	pub disabled: ReadOnlySignal<bool>,
+
+	#[props(extends = form, extends = GlobalAttributes)]
+	pub attributes: Vec<Attribute>,
+	#[props(default = Vec::new())]
+	pub extra_attributes: Vec<Attribute>,
+}
+
+/// A form with a textarea that sends its text to a [`UseChat`], and a button
+/// that stops the answer while it streams.
+#[component]
+pub fn ChatComposer(props: ChatComposerProps) -> Element {
+	let ChatComposerProps { mut chat, send_label, stop_label, placeholder, disabled, attributes, extra_attributes } = props;
+	let mut draft = use_signal(String::new);
+	let loading = chat.loading;
+
+	rsx! {
+		form {
+			"data-loading": loading().then_some(Some(true)),
+			onsubmit: move |event| {
+					event.prevent_default();
+					let text = draft.peek().trim().to_owned();
+					if !text.is_empty() && !loading() && !disabled() {
+							chat.send(text);
+							draft.set(String::new());
+					}
+			},
+			..attributes,
+			..extra_attributes,
+			Textarea {
+				value: Some(draft()),
+				on_value_change: move |value| draft.set(value),
+				disabled: disabled(),
+				placeholder,
+			}
+			if loading() {
+				Button { r#type: "button", onclick: move |_| chat.stop(), "{stop_label}" }
+			} else {
+				Button { r#type: "submit", disabled: disabled(), "{send_label}" }
+			}
+		}
+	}
+}
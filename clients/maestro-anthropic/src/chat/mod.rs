@@ -0,0 +1,4 @@
+mod component;
+mod use_chat;
+
+pub use {component::*, use_chat::*};
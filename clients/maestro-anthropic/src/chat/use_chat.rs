@@ -0,0 +1,162 @@
+use {
+	crate::{
+		Model, Prompt, Tool,
+		chat_event::{ChatError, ChatEvent},
+		chat_message::{ChatMessage, Content, Role, Usage},
+		functions::chat_events_from_server,
+		prompt::System,
+	},
+	dioxus::prelude::*,
+	futures::StreamExt,
+	std::num::NonZeroU16,
+};
+
+/// Settings of the prompts sent by [`use_chat`].
+#[derive(Debug, Clone, PartialEq, bon::Builder)]
+pub struct ChatOptions {
+	#[builder(into)]
+	pub system: Option<System>,
+	#[builder(default)]
+	pub model: Model,
+	#[builder(default = NonZeroU16::new(4096).unwrap())]
+	pub max_tokens: NonZeroU16,
+	#[builder(default = 0.2)]
+	pub temperature: f32,
+	/// Tool uses are shown as they stream but not executed, run an
+	/// [`crate::Agent`] on the server for that.
+	#[builder(default)]
+	pub tools: Vec<Tool>,
+}
+
+impl Default for ChatOptions {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
+
+impl ChatOptions {
+	pub fn prompt(&self, messages: Vec<ChatMessage>) -> Prompt {
+		Prompt::builder()
+			.messages(messages)
+			.maybe_system(self.system.clone())
+			.model(self.model.clone())
+			.max_tokens(self.max_tokens)
+			.temperature(self.temperature)
+			.tools(self.tools.clone())
+			.build(true)
+	}
+}
+
+/// State and actions of a chat streamed through
+/// [`crate::functions::chat_events_from_server`].
+#[derive(Clone, Copy, PartialEq)]
+pub struct UseChat {
+	pub messages: Signal<Vec<ChatMessage>>,
+	pub loading: Signal<bool>,
+	pub error: Signal<Option<ChatError>>,
+	/// Usage of the last answer.
+	pub usage: Signal<Option<Usage>>,
+	options: Signal<ChatOptions>,
+	task: Signal<Option<Task>>,
+}
+
+pub fn use_chat(options: ChatOptions) -> UseChat {
+	let messages = use_signal(Vec::new);
+	let loading = use_signal(|| false);
+	let error = use_signal(|| None);
+	let usage = use_signal(|| None);
+	let options = use_signal(|| options);
+	let task = use_signal(|| None);
+	UseChat { messages, loading, error, usage, options, task }
+}
+
+impl UseChat {
+	/// Append a user message and stream the answer. A running answer is
+	/// stopped first.
+	pub fn send<C>(&mut self, content: C)
+	where
+		C: Into<Content>,
+	{
+		self.stop();
+		self.messages.write().push(ChatMessage::user(content));
+		self.start();
+	}
+
+	/// Stop the running answer. The text received so far is kept.
+	pub fn stop(&mut self) {
+		if let Some(task) = self.task.take() {
+			task.cancel();
+		}
+		self.discard_empty_answer();
+		self.loading.set(false);
+	}
+
+	/// Drop the last answer and ask again.
+	pub fn regenerate(&mut self) {
+		self.stop();
+		if self.messages.peek().last().is_some_and(|message| message.role == Role::Assistant) {
+			self.messages.write().pop();
+		}
+		if self.messages.peek().last().is_some_and(|message| message.role == Role::User) {
+			self.start();
+		}
+	}
+
+	pub fn clear(&mut self) {
+		self.stop();
+		self.messages.write().clear();
+		self.error.set(None);
+		self.usage.set(None);
+	}
+
+	pub fn set_options(&mut self, options: ChatOptions) {
+		self.options.set(options);
+	}
+
+	fn start(&mut self) {
+		let prompt = self.options.peek().prompt(self.messages.peek().clone());
+		self.error.set(None);
+		self.loading.set(true);
+		let mut chat = *self;
+		let task = spawn(async move {
+			let answer = chat.answer(prompt).await;
+			chat.discard_empty_answer();
+			if let Err(error) = answer {
+				chat.error.set(Some(error));
+			}
+			chat.loading.set(false);
+			chat.task.set(None);
+		});
+		self.task.set(Some(task));
+	}
+
+	async fn answer(&mut self, prompt: Prompt) -> Result<(), ChatError> {
+		let stream = chat_events_from_server(prompt).await.map_err(ChatError::from_message)?;
+		let mut events = std::pin::pin!(ChatEvent::decode(stream.into_inner()));
+		self.messages.write().push(ChatMessage::assistant(String::new()));
+		while let Some(event) = events.next().await {
+			match event {
+				ChatEvent::Text { text } =>
+					if let Some(ChatMessage { content: Content::SinglePart(answer), .. }) = self.messages.write().last_mut() {
+						answer.push_str(&text);
+					},
+				ChatEvent::Usage { usage } => self.usage.set(Some(usage)),
+				ChatEvent::Error { error } => return Err(error),
+				_ => {},
+			}
+		}
+		Ok(())
+	}
+
+	/// The API rejects empty messages, so an answer without text, stopped
+	/// before its first token or made only of thinking, is removed.
+	fn discard_empty_answer(&mut self) {
+		let empty = matches!(
+			self.messages.peek().last(),
+			Some(ChatMessage { role: Role::Assistant, content: Content::SinglePart(text) }) if text.is_empty()
+		);
+		if empty {
+			self.messages.write().pop();
+		}
+	}
+}
@@ -0,0 +1,211 @@
+use {
+	crate::{
+		Model,
+		chat_message::{StopReason, Usage, Use},
+	},
+	futures::StreamExt,
+	serde::{Deserialize, Serialize},
+	std::num::NonZeroU16,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+	chat_message::Block,
+	client::{AnthropicClientError, AnthropicError},
+	stream::{self, Accumulator, Delta, Event, Progress},
+};
+
+/// A streamed chat update. The `chat_events_from_server` server function sends
+/// them as one JSON object per line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, derive_more::IsVariant)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum ChatEvent {
+	Start {
+		id: String,
+		model: Model,
+	},
+	Text {
+		text: String,
+	},
+	Thinking {
+		thinking: String,
+	},
+	/// The model started writing the input of a tool.
+	ToolUseStart {
+		index: usize,
+		id: String,
+		name: String,
+	},
+	ToolInput {
+		index: usize,
+		partial_json: String,
+	},
+	/// The complete tool use, once its input has been parsed.
+	ToolUse {
+		index: usize,
+		call: Use,
+	},
+	/// Usage of the message so far.
+	Usage {
+		usage: Usage,
+	},
+	Stop {
+		stop_reason: Option<StopReason>,
+	},
+	Error {
+		error: ChatError,
+	},
+}
+
+impl ChatEvent {
+	/// The event as a JSON line.
+	pub fn to_line(&self) -> String {
+		let mut line = serde_json::to_string(self)
+			.unwrap_or_else(|error| serde_json::to_string(&Self::Error { error: ChatError::from_message(error) }).expect("an error event always serializes"));
+		line.push('\n');
+		line
+	}
+
+	/// Decode a stream of text chunks holding JSON lines. Chunks do not have
+	/// to end on a line boundary. Transport and parse errors become
+	/// [`ChatEvent::Error`]s.
+	pub fn decode<S, E>(chunks: S) -> impl futures::Stream<Item = ChatEvent>
+	where
+		S: futures::Stream<Item = Result<String, E>>,
+		E: std::fmt::Display,
+	{
+		chunks
+			.scan(String::new(), |buffer, chunk| {
+				let events = match chunk {
+					Ok(chunk) => {
+						buffer.push_str(&chunk);
+						let end = buffer.rfind('\n').map_or(0, |end| end + 1);
+						buffer.drain(..end).collect::<String>().lines().filter(|line| !line.trim().is_empty()).map(Self::parse).collect()
+					},
+					Err(error) => vec![Self::Error { error: ChatError::from_message(error) }],
+				};
+				std::future::ready(Some(futures::stream::iter(events)))
+			})
+			.flatten()
+	}
+
+	fn parse(line: &str) -> Self {
+		serde_json::from_str(line).unwrap_or_else(|error| Self::Error { error: ChatError::from_message(error) })
+	}
+}
+
+/// An error sent to the client. `status` is the HTTP status of API errors.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatError {
+	pub message: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub status: Option<NonZeroU16>,
+	/// Rate limits and overloads, the request can be sent again later.
+	#[serde(default)]
+	pub retryable: bool,
+}
+
+impl ChatError {
+	pub fn from_message<M>(message: M) -> Self
+	where
+		M: std::fmt::Display,
+	{
+		Self { message: message.to_string(), status: None, retryable: false }
+	}
+}
+
+impl std::fmt::Display for ChatError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.status {
+			Some(status) => write!(f, "{} ({status})", self.message),
+			None => write!(f, "{}", self.message),
+		}
+	}
+}
+
+impl std::error::Error for ChatError {}
+
+#[cfg(feature = "server")]
+impl From<&AnthropicError> for ChatError {
+	fn from(error: &AnthropicError) -> Self {
+		Self {
+			message: error.to_string(),
+			status: Some(error.status()),
+			retryable: matches!(error, AnthropicError::RateLimit { .. } | AnthropicError::Overloaded { .. }),
+		}
+	}
+}
+
+#[cfg(feature = "server")]
+impl From<&stream::Error> for ChatError {
+	fn from(error: &stream::Error) -> Self {
+		match error {
+			stream::Error::Anthropic { error, .. } => error.into(),
+			error => Self::from_message(error),
+		}
+	}
+}
+
+#[cfg(feature = "server")]
+impl From<&AnthropicClientError> for ChatError {
+	fn from(error: &AnthropicClientError) -> Self {
+		match error {
+			AnthropicClientError::Anthropic(error) => error.into(),
+			AnthropicClientError::Stream(error) => error.into(),
+			error => Self::from_message(error),
+		}
+	}
+}
+
+/// Turns stream [`Event`]s into [`ChatEvent`]s, using an [`Accumulator`] to
+/// send tool uses once their input is complete.
+#[cfg(feature = "server")]
+#[derive(Debug, Default)]
+pub struct ChatEventEncoder {
+	accumulator: Accumulator,
+}
+
+#[cfg(feature = "server")]
+impl ChatEventEncoder {
+	pub fn apply(&mut self, event: Result<Event, stream::Error>) -> Vec<ChatEvent> {
+		let event = match event {
+			Ok(event) => event,
+			Err(error) => return vec![ChatEvent::Error { error: (&error).into() }],
+		};
+		let mut events = match &event {
+			Event::MessageStart { message } => vec![ChatEvent::Start { id: message.id.clone(), model: message.model.clone() }],
+			Event::ContentBlockStart { index, content_block: Block::ToolUse { call, .. } } => {
+				vec![ChatEvent::ToolUseStart { index: *index, id: call.id.clone(), name: call.name.clone() }]
+			},
+			Event::ContentBlockStart { content_block: Block::Text { text, .. }, .. } if !text.is_empty() => vec![ChatEvent::Text { text: text.clone() }],
+			Event::ContentBlockDelta { delta: Delta::Text { text }, .. } => vec![ChatEvent::Text { text: text.clone() }],
+			Event::ContentBlockDelta { delta: Delta::Thinking { thinking }, .. } => vec![ChatEvent::Thinking { thinking: thinking.clone() }],
+			Event::ContentBlockDelta { index, delta: Delta::Json { partial_json } } => {
+				vec![ChatEvent::ToolInput { index: *index, partial_json: partial_json.clone() }]
+			},
+			_ => vec![],
+		};
+		let stopped = match &event {
+			Event::ContentBlockStop { index } => Some(*index),
+			_ => None,
+		};
+		let stats = matches!(event, Event::MessageStats { .. });
+		let progress = self.accumulator.apply(event);
+		match progress.map(|progress| progress.zip(self.accumulator.message())) {
+			Ok(Some((Progress::Complete, message))) => events.push(ChatEvent::Stop { stop_reason: message.stop_reason.clone() }),
+			Ok(Some((Progress::Partial, message))) => {
+				if let Some(index) = stopped
+					&& let Some(call) = message.message.content.blocks().nth(index).and_then(Block::tool_use)
+				{
+					events.push(ChatEvent::ToolUse { index, call: call.clone() });
+				}
+				if stats {
+					events.push(ChatEvent::Usage { usage: message.usage.clone() });
+				}
+			},
+			Ok(None) => {},
+			Err(error) => events.push(ChatEvent::Error { error: (&error).into() }),
+		}
+		events
+	}
+}
@@ -1,6 +1,9 @@
-use serde::{Deserialize, Serialize};
+use {
+	crate::media::{MediaType, Source, UnsupportedMedia},
+	serde::{Deserialize, Serialize},
+};
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, strum_macros::Display, strum_macros::AsRefStr)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum_macros::Display, strum_macros::AsRefStr)]
 #[serde(rename_all = "snake_case")]
 pub enum Role {
 	User,
@@ -10,5 +13,351 @@ pub enum Role {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ChatMessage {
 	pub role: Role,
-	pub content: String,
+	pub content: Content,
+}
+
+impl ChatMessage {
+	pub fn user<C>(content: C) -> Self
+	where
+		C: Into<Content>,
+	{
+		Self { role: Role::User, content: content.into() }
+	}
+
+	pub fn assistant<C>(content: C) -> Self
+	where
+		C: Into<Content>,
+	{
+		Self { role: Role::Assistant, content: content.into() }
+	}
+
+	/// Set a cache breakpoint on the last block of this message, caching the
+	/// conversation up to here.
+	#[must_use]
+	pub fn cached(mut self) -> Self {
+		if let Content::SinglePart(text) = &mut self.content {
+			self.content = Content::MultiPart(vec![Block::text(std::mem::take(text))]);
+		}
+		if let Content::MultiPart(parts) = &mut self.content
+			&& let Some(last) = parts.pop()
+		{
+			parts.push(last.cached());
+		}
+		self
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, derive_more::IsVariant)]
+#[serde(rename_all = "snake_case")]
+#[serde(untagged)]
+pub enum Content {
+	SinglePart(String),
+	MultiPart(Vec<Block>),
+}
+
+impl Content {
+	pub const SEP: &'static str = "\n\n";
+
+	pub fn text<T>(text: T) -> Self
+	where
+		T: Into<String>,
+	{
+		Self::SinglePart(text.into())
+	}
+
+	pub fn unwrap_single_part(self) -> Block {
+		match self {
+			Self::SinglePart(text) => Block::text(text),
+			Self::MultiPart(_) => {
+				panic!("Content is MultiPart, not SinglePart");
+			},
+		}
+	}
+
+	pub fn push<P>(&mut self, part: P)
+	where
+		P: Into<Block>,
+	{
+		if self.is_single_part() {
+			let mut old = Content::MultiPart(vec![]);
+			std::mem::swap(self, &mut old);
+			self.push(old.unwrap_single_part());
+		}
+
+		if let Content::MultiPart(parts) = self {
+			parts.push(part.into());
+		}
+	}
+
+	pub fn last(&self) -> Option<&Block> {
+		match self {
+			Self::SinglePart(_) => None,
+			Self::MultiPart(parts) => parts.last(),
+		}
+	}
+
+	/// The text blocks joined by [`Self::SEP`], other blocks are skipped.
+	pub fn plain_text(&self) -> String {
+		match self {
+			Self::SinglePart(text) => text.clone(),
+			Self::MultiPart(parts) => parts
+				.iter()
+				.filter_map(|block| match block {
+					Block::Text { text, .. } => Some(text.as_str()),
+					_ => None,
+				})
+				.collect::<Vec<_>>()
+				.join(Self::SEP),
+		}
+	}
+
+	/// Iterate over the blocks of this content. A [`Content::SinglePart`] has
+	/// no blocks.
+	pub fn blocks(&self) -> impl Iterator<Item = &Block> {
+		match self {
+			Self::SinglePart(_) => [].iter(),
+			Self::MultiPart(parts) => parts.iter(),
+		}
+	}
+}
+
+impl From<String> for Content {
+	fn from(text: String) -> Self {
+		Self::SinglePart(text)
+	}
+}
+
+impl From<&str> for Content {
+	fn from(text: &str) -> Self {
+		Self::SinglePart(text.to_owned())
+	}
+}
+
+impl From<Vec<Block>> for Content {
+	fn from(blocks: Vec<Block>) -> Self {
+		Self::MultiPart(blocks)
+	}
+}
+
+impl std::fmt::Display for Content {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::SinglePart(string) => write!(f, "{string}"),
+			// This could be derived but the `Join` trait is not stable. Neither
+			// is `Iterator::intersperse`. This also has fewer allocations.
+			Self::MultiPart(parts) => {
+				let mut iter = parts.iter();
+				if let Some(part) = iter.next() {
+					write!(f, "{part}")?;
+					for part in iter {
+						write!(f, "{}{}", Self::SEP, part)?;
+					}
+				}
+				Ok(())
+			},
+		}
+	}
+}
+
+/// Marks the end of a cacheable prefix of the prompt. Everything up to and
+/// including the marked system text, tool or block is cached.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum CacheControl {
+	#[default]
+	Ephemeral,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, strum_macros::Display)]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "type")]
+pub enum Block {
+	#[serde(alias = "text_delta")]
+	Text {
+		text: String,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		cache_control: Option<CacheControl>,
+	},
+	ToolUse {
+		#[serde(flatten)]
+		call: Use,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		cache_control: Option<CacheControl>,
+	},
+	ToolResult {
+		#[serde(flatten)]
+		call: ToolBlock,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		cache_control: Option<CacheControl>,
+	},
+	Image {
+		source: Source,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		cache_control: Option<CacheControl>,
+	},
+	Document {
+		source: Source,
+		#[serde(skip_serializing_if = "Option::is_none")]
+		title: Option<String>,
+		#[serde(skip_serializing_if = "Option::is_none")]
+		context: Option<String>,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		cache_control: Option<CacheControl>,
+	},
+	/// Extended thinking. It must be sent back unchanged, signature included,
+	/// when continuing a tool use turn.
+	Thinking {
+		thinking: String,
+		#[serde(default)]
+		signature: String,
+	},
+	RedactedThinking {
+		data: String,
+	},
+}
+
+impl Block {
+	pub fn text<T>(text: T) -> Self
+	where
+		T: Into<String>,
+	{
+		Self::Text { text: text.into(), cache_control: None }
+	}
+
+	pub fn image(source: Source) -> Self {
+		Self::Image { source, cache_control: None }
+	}
+
+	pub fn document(source: Source) -> Self {
+		Self::Document { source, title: None, context: None, cache_control: None }
+	}
+
+	/// Set a cache breakpoint on this block. Thinking blocks cannot be marked
+	/// and are returned unchanged.
+	#[must_use]
+	pub fn cached(mut self) -> Self {
+		match &mut self {
+			Self::Text { cache_control, .. }
+			| Self::ToolUse { cache_control, .. }
+			| Self::ToolResult { cache_control, .. }
+			| Self::Image { cache_control, .. }
+			| Self::Document { cache_control, .. } => *cache_control = Some(CacheControl::Ephemeral),
+			Self::Thinking { .. } | Self::RedactedThinking { .. } => {},
+		}
+		self
+	}
+
+	pub fn is_thinking(&self) -> bool {
+		matches!(self, Self::Thinking { .. } | Self::RedactedThinking { .. })
+	}
+
+	/// Build an image or a PDF document block from raw bytes, depending on the
+	/// detected [`MediaType`].
+	///
+	/// # Errors
+	///
+	/// Fails when the media type is not supported by the API.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, UnsupportedMedia> {
+		let source = Source::from_bytes(bytes)?;
+		Ok(match source.media_type() {
+			Some(MediaType::Pdf) => Self::document(source),
+			_ => Self::image(source),
+		})
+	}
+
+	pub fn image_url<U>(url: U) -> Self
+	where
+		U: Into<String>,
+	{
+		Self::image(Source::url(url))
+	}
+
+	pub fn document_url<U>(url: U) -> Self
+	where
+		U: Into<String>,
+	{
+		Self::document(Source::url(url))
+	}
+
+	pub fn tool_use(&self) -> Option<&Use> {
+		match self {
+			Self::ToolUse { call, .. } => Some(call),
+			_ => None,
+		}
+	}
+}
+
+impl From<Use> for Block {
+	fn from(call: Use) -> Self {
+		Self::ToolUse { call, cache_control: None }
+	}
+}
+
+impl From<ToolBlock> for Block {
+	fn from(call: ToolBlock) -> Self {
+		Self::ToolResult { call, cache_control: None }
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Use {
+	pub id: String,
+	pub name: String,
+	pub input: serde_json::Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolBlock {
+	pub tool_use_id: String,
+	pub content: Content,
+	pub is_error: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+	EndTurn,
+	MaxTokens,
+	StopSequence,
+	ToolUse,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Usage {
+	pub input_tokens: u64,
+	pub output_tokens: u64,
+	pub cache_creation_input_tokens: u64,
+	pub cache_read_input_tokens: u64,
+}
+
+impl Usage {
+	/// Apply the usage of a `message_delta` event. Its counts are cumulative
+	/// and input counts are usually omitted, so only non-zero inputs replace
+	/// the current ones.
+	pub fn apply_delta(&mut self, delta: &Usage) {
+		self.output_tokens = delta.output_tokens;
+		if delta.input_tokens > 0 {
+			self.input_tokens = delta.input_tokens;
+		}
+		if delta.cache_creation_input_tokens > 0 {
+			self.cache_creation_input_tokens = delta.cache_creation_input_tokens;
+		}
+		if delta.cache_read_input_tokens > 0 {
+			self.cache_read_input_tokens = delta.cache_read_input_tokens;
+		}
+	}
+
+	pub fn total_tokens(&self) -> u64 {
+		self.input_tokens + self.output_tokens + self.cache_creation_input_tokens + self.cache_read_input_tokens
+	}
+}
+
+impl std::ops::AddAssign<&Usage> for Usage {
+	fn add_assign(&mut self, other: &Usage) {
+		self.input_tokens += other.input_tokens;
+		self.output_tokens += other.output_tokens;
+		self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+		self.cache_read_input_tokens += other.cache_read_input_tokens;
+	}
 }
@@ -1,54 +1,158 @@
 use {
 	super::{
-		prompt::{IsComplete, PromptBuilder},
-		AnthropicStream,
+		AnthropicStream, Model, Prompt, Tool, TypedTool,
+		chat_message::ChatMessage,
+		prompt::{IsComplete, PromptBuilder, System, Thinking},
 	},
-	crate::prompt::{SetMessages, SetSystem, SetToolChoice, SetTools},
-	eventsource_stream::Eventsource,
-	reqwest::{
-		header::{HeaderMap, HeaderValue, CONTENT_TYPE},
-		Client, Method, StatusCode,
+	crate::{
+		prompt::{SetMessages, SetSystem, SetToolChoice, SetTools},
+		tool,
+		transport::{HttpRequest, HttpResponse, ReqwestTransport, Transport},
+		usage::TokenCount,
 	},
-	serde::{Deserialize, Serialize},
-	std::{env, num::NonZeroU16},
+	eventsource_stream::Eventsource,
+	reqwest::header::InvalidHeaderValue,
+	serde::{Deserialize, Serialize, de::DeserializeOwned},
+	std::{num::NonZeroU16, sync::Arc, time::Duration},
 };
 
 pub type AnthropicResult<T> = std::result::Result<T, AnthropicClientError>;
 
 #[derive(Clone)]
 pub struct AnthropicClient {
-	pub(crate) inner: Client,
+	transport: Arc<dyn Transport>,
+	timeout: Option<Duration>,
+	retry: RetryPolicy,
 }
 
+#[bon::bon]
 impl AnthropicClient {
 	pub const ANTHROPIC_VERSION: &'static str = "2023-06-01";
-	pub const DEFAULT_URL: &'static str = "https://api.anthropic.com/v1/messages";
+	pub const API_KEY_VAR: &'static str = "ANTHROPIC_API_KEY";
+	pub const COUNT_TOKENS_PATH: &'static str = "/v1/messages/count_tokens";
+	pub const DEFAULT_BASE_URL: &'static str = "https://api.anthropic.com";
+	pub const MESSAGES_PATH: &'static str = "/v1/messages";
 	pub const USER_AGENT: &'static str = concat!(env!("CARGO_PKG_NAME"), "-", env!("CARGO_PKG_VERSION"));
 
-	pub fn new(key: &str) -> Self {
-		let mut val = HeaderValue::from_bytes(key.as_bytes()).unwrap();
-		val.set_sensitive(true);
-		let mut headers = HeaderMap::new();
-		headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-		headers.insert("anthropic-version", HeaderValue::from_static(Self::ANTHROPIC_VERSION));
-		headers.insert("x-api-key", val);
-		Self { inner: Client::builder().default_headers(headers).build().unwrap() }
+	/// Create a client with the default configuration.
+	///
+	/// # Errors
+	///
+	/// Fails when `key` is not a valid header value.
+	pub fn new(key: &str) -> AnthropicResult<Self> {
+		Self::builder().key(key).build()
+	}
+
+	/// Create a client with the default configuration and the key from
+	/// [`Self::API_KEY_VAR`].
+	///
+	/// # Errors
+	///
+	/// Fails when the variable is missing or is not a valid header value.
+	pub fn from_env() -> AnthropicResult<Self> {
+		Self::builder().build()
+	}
+
+	/// Create a client sending its requests through `transport`, e.g. a
+	/// [`crate::transport::MockTransport`] in tests.
+	pub fn with_transport(transport: Arc<dyn Transport>) -> Self {
+		Self { transport, timeout: None, retry: RetryPolicy::default() }
+	}
+
+	/// Create a client. Prefer [`Self::builder`].
+	///
+	/// `key` defaults to [`Self::API_KEY_VAR`] and `base_url` to
+	/// [`Self::DEFAULT_BASE_URL`], point it to a proxy or a local mock server.
+	/// `timeout` bounds a whole non-streaming request and the time between
+	/// two chunks of a stream. `transport` replaces the HTTP transport, `key`
+	/// and `base_url` are then ignored.
+	///
+	/// # Errors
+	///
+	/// Fails when no key is found, the key is not a valid header value or the
+	/// HTTP client cannot be built.
+	#[builder(start_fn = builder, finish_fn = build, builder_type = AnthropicClientBuilder)]
+	pub fn configure(
+		#[builder(into)] key: Option<String>,
+		#[builder(into, default = AnthropicClient::DEFAULT_BASE_URL)] base_url: String,
+		timeout: Option<Duration>,
+		#[builder(default)] retry: RetryPolicy,
+		transport: Option<Arc<dyn Transport>>,
+	) -> AnthropicResult<Self> {
+		let transport = match transport {
+			Some(transport) => transport,
+			None => Arc::new(ReqwestTransport::builder().maybe_key(key).base_url(base_url).maybe_timeout(timeout).build()?),
+		};
+		Ok(Self { transport, timeout, retry })
 	}
 
 	pub async fn message<S: IsComplete>(&self, prompt: PromptBuilder<S>) -> AnthropicResult<super::response::ResponseMessage> {
-		let response = self.inner.request(Method::POST, Self::DEFAULT_URL).json(&prompt.build(false)).send().await?;
-		if response.status() != StatusCode::OK {
-			return Err(response.json::<AnthropicErrorWrapper>().await?.error.into());
-		}
-		Ok(serde_json::from_slice(&response.bytes().await?)?)
+		self.chat(prompt.build(false)).await
 	}
 
 	pub async fn stream<S: IsComplete>(&self, prompt: PromptBuilder<S>) -> AnthropicResult<AnthropicStream> {
-		let response = self.inner.request(Method::POST, Self::DEFAULT_URL).json(&prompt.build(true)).send().await?;
-		if response.status() != StatusCode::OK {
-			return Err(response.json::<AnthropicErrorWrapper>().await?.error.into());
+		self.stream_chat(prompt.build(true)).await
+	}
+
+	/// Send an already built [`Prompt`] after [`Prompt::validate`]. The
+	/// `stream` flag of the prompt is overridden.
+	pub async fn chat(&self, mut prompt: Prompt) -> AnthropicResult<super::response::ResponseMessage> {
+		prompt.stream = false;
+		prompt.validate()?;
+		self.post(&prompt).await?.json().await
+	}
+
+	/// Stream an already built [`Prompt`] as [`crate::stream::Event`]s after
+	/// [`Prompt::validate`]. The `stream` flag of the prompt is overridden.
+	pub async fn stream_chat(&self, mut prompt: Prompt) -> AnthropicResult<AnthropicStream> {
+		prompt.stream = true;
+		prompt.validate()?;
+		let response = self.post(&prompt).await?;
+		Ok(AnthropicStream::new(response.body.eventsource()))
+	}
+
+	/// Count the input tokens of `prompt` without sending it to the model.
+	pub async fn count_tokens(&self, prompt: &Prompt) -> AnthropicResult<TokenCount> {
+		let request = CountTokensRequest {
+			model: &prompt.model,
+			messages: &prompt.messages,
+			system: prompt.system.as_ref(),
+			thinking: prompt.thinking.as_ref(),
+			tools: &prompt.tools,
+			tool_choice: prompt.tool_choice.as_ref(),
+		};
+		self.execute_json(HttpRequest::post(Self::COUNT_TOKENS_PATH, &request)?).await
+	}
+
+	async fn post(&self, prompt: &Prompt) -> AnthropicResult<HttpResponse> {
+		let timeout = if prompt.stream { None } else { self.timeout };
+		self.execute(HttpRequest::post(Self::MESSAGES_PATH, prompt)?.timeout(timeout)).await
+	}
+
+	/// Send `request` through the transport, retrying it according to the
+	/// [`RetryPolicy`] while the API reports rate limits or overloads.
+	pub(crate) async fn execute(&self, request: HttpRequest) -> AnthropicResult<HttpResponse> {
+		let mut attempt = 0;
+		loop {
+			let response = self.transport.send(request.clone()).await?;
+			if response.is_success() {
+				return Ok(response);
+			}
+			let retry_after = response.retry_after;
+			let error = response.json::<AnthropicErrorWrapper>().await?.error;
+			match self.retry.delay(attempt, &error, retry_after) {
+				Some(delay) => tokio::time::sleep(delay).await,
+				None => return Err(error.into()),
+			}
+			attempt += 1;
 		}
-		Ok(AnthropicStream::new(response.bytes_stream().eventsource()))
+	}
+
+	pub(crate) async fn execute_json<T>(&self, request: HttpRequest) -> AnthropicResult<T>
+	where
+		T: DeserializeOwned,
+	{
+		self.execute(request).await?.json().await
 	}
 
 	pub async fn call_tool(&self, prompt: PromptBuilder<SetToolChoice<SetTools<SetSystem<SetMessages>>>>) -> AnthropicResult<serde_json::Value> {
@@ -62,6 +166,73 @@ impl AnthropicClient {
 			.map(|tool_use| tool_use.input.to_owned())
 			.ok_or(AnthropicClientError::UnexpectedResponse { message: "No tool use result found" })
 	}
+
+	/// Same as [`Self::call_tool`] but decodes the input into `T`.
+	pub async fn call_typed_tool<T: TypedTool>(&self, prompt: PromptBuilder<SetToolChoice<SetTools<SetSystem<SetMessages>>>>) -> AnthropicResult<T> {
+		let response = self.message(prompt).await?;
+		let call = response
+			.message
+			.content
+			.last()
+			.and_then(|block| block.tool_use())
+			.ok_or(AnthropicClientError::UnexpectedResponse { message: "No tool use result found" })?;
+		Ok(T::decode(call)?)
+	}
+}
+
+/// Exponential backoff applied to [`AnthropicError::RateLimit`] and
+/// [`AnthropicError::Overloaded`] responses. The `retry-after` header sent by
+/// the API takes precedence over the computed delay.
+#[derive(Debug, Clone, Copy, PartialEq, bon::Builder)]
+pub struct RetryPolicy {
+	#[builder(default = 3)]
+	pub max_retries: u32,
+	#[builder(default = Duration::from_millis(500))]
+	pub initial_backoff: Duration,
+	/// Also caps the `retry-after` header.
+	#[builder(default = Duration::from_secs(30))]
+	pub max_backoff: Duration,
+	/// At least 1, the setter of the builder rejects smaller or non-finite
+	/// values.
+	#[builder(default = 2.0, with = |multiplier: f64| -> Result<_, InvalidMultiplier> {
+		if multiplier.is_finite() && multiplier >= 1.0 { Ok(multiplier) } else { Err(InvalidMultiplier { multiplier }) }
+	})]
+	pub multiplier: f64,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("The backoff multiplier must be finite and at least 1, got {multiplier}")]
+pub struct InvalidMultiplier {
+	pub multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
+
+impl RetryPolicy {
+	pub fn none() -> Self {
+		Self::builder().max_retries(0).build()
+	}
+
+	/// The computed delay before retry `attempt`, [`Self::max_backoff`] when it
+	/// does not fit a [`Duration`].
+	pub fn backoff(&self, attempt: u32) -> Duration {
+		let exponent = i32::try_from(attempt).unwrap_or(i32::MAX);
+		Duration::try_from_secs_f64(self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent))
+			.map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+	}
+
+	/// How long to wait before retrying after `error`, `None` when the request
+	/// should not be retried.
+	pub fn delay(&self, attempt: u32, error: &AnthropicError, retry_after: Option<Duration>) -> Option<Duration> {
+		if attempt >= self.max_retries || !matches!(error, AnthropicError::RateLimit { .. } | AnthropicError::Overloaded { .. }) {
+			return None;
+		}
+		Some(retry_after.unwrap_or_else(|| self.backoff(attempt)).min(self.max_backoff))
+	}
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -76,9 +247,23 @@ pub enum AnthropicClientError {
 	#[error("Unexpected response: {message}")]
 	#[allow(missing_docs)]
 	UnexpectedResponse { message: &'static str },
+	#[error("Stream error: {0}")]
+	Stream(#[from] crate::stream::Error),
+	#[error("Missing API key, pass one or set `{}`", AnthropicClient::API_KEY_VAR)]
+	MissingKey,
+	#[error("Invalid API key: {0}")]
+	InvalidKey(#[from] InvalidHeaderValue),
+	#[error("Tool input error: {0}")]
+	ToolInput(#[from] crate::tool::ToolInputError),
+	#[error("Transport error: {message}")]
+	Transport { message: String },
+	#[error("Invalid prompt: {0}")]
+	InvalidPrompt(#[from] crate::prompt::PromptError),
+	#[error("Agent did not finish its turn within {iterations} iterations")]
+	MaxIterations { iterations: NonZeroU16 },
 }
 
-#[derive(Debug, thiserror::Error, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 #[serde(tag = "type")]
 #[allow(missing_docs)]
@@ -129,8 +314,60 @@ impl AnthropicError {
 	}
 }
 
+/// The subset of [`Prompt`] accepted by the count tokens endpoint.
+#[derive(Serialize)]
+struct CountTokensRequest<'a> {
+	model: &'a Model,
+	messages: &'a [ChatMessage],
+	#[serde(skip_serializing_if = "Option::is_none")]
+	system: Option<&'a System>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	thinking: Option<&'a Thinking>,
+	#[serde(skip_serializing_if = "<[Tool]>::is_empty")]
+	tools: &'a [Tool],
+	#[serde(skip_serializing_if = "Option::is_none")]
+	tool_choice: Option<&'a tool::Choice>,
+}
+
 #[derive(Deserialize)]
 #[serde(tag = "error")]
 pub(crate) struct AnthropicErrorWrapper {
 	pub(crate) error: AnthropicError,
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn rate_limit() -> AnthropicError {
+		AnthropicError::RateLimit { message: "Slow down".to_owned() }
+	}
+
+	#[test]
+	fn backoff_saturates_at_the_maximum() {
+		let retry = RetryPolicy::default();
+		assert_eq!(retry.backoff(0), Duration::from_millis(500));
+		assert_eq!(retry.backoff(2), Duration::from_secs(2));
+		assert_eq!(retry.backoff(65), retry.max_backoff);
+		assert_eq!(retry.backoff(u32::MAX), retry.max_backoff);
+		let negative = RetryPolicy { multiplier: -3.0, ..retry };
+		assert_eq!(negative.backoff(1), retry.max_backoff);
+		let nan = RetryPolicy { multiplier: f64::NAN, ..retry };
+		assert_eq!(nan.backoff(1), retry.max_backoff);
+	}
+
+	#[test]
+	fn builder_rejects_shrinking_multipliers() {
+		assert!(RetryPolicy::builder().multiplier(0.5).is_err());
+		assert!(RetryPolicy::builder().multiplier(f64::INFINITY).is_err());
+		assert_eq!(RetryPolicy::builder().multiplier(1.0).unwrap().build().backoff(10), Duration::from_millis(500));
+	}
+
+	#[test]
+	fn retry_after_is_capped() {
+		let retry = RetryPolicy::default();
+		assert_eq!(retry.delay(0, &rate_limit(), Some(Duration::from_secs(3600))), Some(retry.max_backoff));
+		assert_eq!(retry.delay(0, &rate_limit(), Some(Duration::from_secs(1))), Some(Duration::from_secs(1)));
+		assert_eq!(retry.delay(3, &rate_limit(), None), None);
+	}
+}
@@ -0,0 +1,317 @@
+use {
+	crate::{
+		Model, Prompt,
+		chat_message::{Block, ChatMessage, Content, Role, ToolBlock},
+		prompt::{PromptBuilder, SetMessages, SetSystem, System},
+	},
+	serde::{Deserialize, Serialize},
+};
+
+/// A chat history that always alternates between user and assistant messages,
+/// starting with the user. Tool results are user messages made of
+/// [`Block::ToolResult`]s answering the previous assistant message.
+///
+/// It serializes to JSON, so it can be stored as a single column.
+/// Deserialization checks the alternation too.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedConversation")]
+pub struct Conversation {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	system: Option<System>,
+	messages: Vec<ChatMessage>,
+}
+
+#[derive(Deserialize)]
+struct UncheckedConversation {
+	#[serde(default)]
+	system: Option<System>,
+	#[serde(default)]
+	messages: Vec<ChatMessage>,
+}
+
+impl TryFrom<UncheckedConversation> for Conversation {
+	type Error = ConversationError;
+
+	fn try_from(unchecked: UncheckedConversation) -> Result<Self, Self::Error> {
+		let mut conversation = Self { system: unchecked.system, messages: Vec::with_capacity(unchecked.messages.len()) };
+		for message in unchecked.messages {
+			conversation.push(message)?;
+		}
+		Ok(conversation)
+	}
+}
+
+impl Conversation {
+	/// Rough number of tokens an image or a document costs. Real costs depend
+	/// on the image size and the number of pages.
+	pub const MEDIA_TOKENS: u32 = 1600;
+
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn with_system<S>(system: S) -> Self
+	where
+		S: Into<System>,
+	{
+		Self { system: Some(system.into()), messages: Vec::new() }
+	}
+
+	pub fn system(&self) -> Option<&System> {
+		self.system.as_ref()
+	}
+
+	pub fn set_system<S>(&mut self, system: Option<S>)
+	where
+		S: Into<System>,
+	{
+		self.system = system.map(Into::into);
+	}
+
+	pub fn messages(&self) -> &[ChatMessage] {
+		&self.messages
+	}
+
+	pub fn last(&self) -> Option<&ChatMessage> {
+		self.messages.last()
+	}
+
+	pub fn len(&self) -> usize {
+		self.messages.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.messages.is_empty()
+	}
+
+	/// The role the next message must have.
+	pub fn next_role(&self) -> Role {
+		match self.messages.last() {
+			Some(ChatMessage { role: Role::User, .. }) => Role::Assistant,
+			_ => Role::User,
+		}
+	}
+
+	/// # Errors
+	///
+	/// Fails when `message` does not have [`Self::next_role`].
+	pub fn push(&mut self, message: ChatMessage) -> Result<(), ConversationError> {
+		let expected = self.next_role();
+		if message.role != expected {
+			return Err(ConversationError::Alternation { expected, found: message.role });
+		}
+		self.messages.push(message);
+		Ok(())
+	}
+
+	/// # Errors
+	///
+	/// Fails when the last message is from the user.
+	pub fn push_user<C>(&mut self, content: C) -> Result<(), ConversationError>
+	where
+		C: Into<Content>,
+	{
+		self.push(ChatMessage::user(content))
+	}
+
+	/// # Errors
+	///
+	/// Fails when the last message is from the assistant.
+	pub fn push_assistant<C>(&mut self, content: C) -> Result<(), ConversationError>
+	where
+		C: Into<Content>,
+	{
+		self.push(ChatMessage::assistant(content))
+	}
+
+	/// Answer the tool uses of the last assistant message.
+	///
+	/// # Errors
+	///
+	/// Fails when the last message is from the user.
+	pub fn push_tool_results<R>(&mut self, results: R) -> Result<(), ConversationError>
+	where
+		R: IntoIterator<Item = ToolBlock>,
+	{
+		self.push(ChatMessage::user(results.into_iter().map(Block::from).collect::<Vec<_>>()))
+	}
+
+	/// A prompt builder with the system prompt and the messages already set.
+	pub fn prompt(&self) -> PromptBuilder<SetSystem<SetMessages>> {
+		Prompt::builder().messages(self.messages.clone()).maybe_system(self.system.clone())
+	}
+
+	/// Rough token count of the system prompt and the messages, about four
+	/// characters per token. Use [`crate::AnthropicClient::count_tokens`] for
+	/// an exact count.
+	pub fn estimate_tokens(&self) -> u32 {
+		let system = match &self.system {
+			Some(System::Text(text)) => text_tokens(text),
+			Some(System::Blocks(blocks)) => blocks.iter().map(block_tokens).fold(0, u32::saturating_add),
+			None => 0,
+		};
+		self.messages.iter().map(|message| content_tokens(&message.content)).fold(system, u32::saturating_add)
+	}
+
+	/// Drop the oldest turns until [`Self::estimate_tokens`] fits in `budget`.
+	/// A turn starts with a user message that is not made of tool results, so
+	/// tool uses are never separated from their results. The last turn is
+	/// always kept. Returns the dropped messages.
+	pub fn trim(&mut self, budget: u32) -> Vec<ChatMessage> {
+		let mut dropped = Vec::new();
+		while self.estimate_tokens() > budget {
+			let Some(next_turn) = self.messages.iter().skip(1).position(is_turn_start).map(|index| index + 1) else {
+				break;
+			};
+			dropped.extend(self.messages.drain(..next_turn));
+		}
+		dropped
+	}
+
+	/// Trim the conversation so that it fits in the context window of `model`
+	/// with room for `max_tokens` of output.
+	pub fn trim_for(&mut self, model: &Model, max_tokens: u32) -> Vec<ChatMessage> {
+		self.trim(model.context_window().saturating_sub(max_tokens))
+	}
+
+	/// Prepend `text` to the first message, to carry a summary of the turns
+	/// dropped by [`Self::trim`].
+	pub fn prepend<T>(&mut self, text: T)
+	where
+		T: Into<String>,
+	{
+		if let Some(first) = self.messages.first_mut() {
+			let blocks = match std::mem::replace(&mut first.content, Content::MultiPart(Vec::new())) {
+				Content::SinglePart(rest) => vec![Block::text(text), Block::text(rest)],
+				Content::MultiPart(rest) => std::iter::once(Block::text(text)).chain(rest).collect(),
+			};
+			first.content = Content::MultiPart(blocks);
+		}
+	}
+}
+
+#[cfg(feature = "server")]
+impl Conversation {
+	pub const SUMMARY_MAX_TOKENS: u32 = 8192;
+	pub const SUMMARY_PROMPT: &'static str =
+		"Summarize the conversation so far in a few paragraphs. Keep names, figures, decisions and open questions. Reply with the summary only.";
+
+	/// Append the message of a response.
+	///
+	/// # Errors
+	///
+	/// Fails when the last message is from the assistant.
+	pub fn record(&mut self, response: &crate::ResponseMessage) -> Result<(), ConversationError> {
+		self.push(response.message.clone())
+	}
+
+	/// Like [`Self::trim`], but the dropped turns are summarized by `model` and
+	/// the summary is prepended to the first remaining message. The summary is
+	/// limited to [`Self::SUMMARY_MAX_TOKENS`] or the output limit of `model`.
+	///
+	/// # Errors
+	///
+	/// Fails when the summary request fails. The conversation is left
+	/// untouched.
+	pub async fn summarize(&mut self, client: &crate::AnthropicClient, model: Model, budget: u32) -> crate::client::AnthropicResult<()> {
+		let mut trimmed = self.clone();
+		let mut messages = trimmed.trim(budget);
+		if messages.is_empty() {
+			return Ok(());
+		}
+		messages.push(ChatMessage::user(Self::SUMMARY_PROMPT));
+		let max_tokens =
+			u16::try_from(model.max_output_tokens().min(Self::SUMMARY_MAX_TOKENS)).ok().and_then(std::num::NonZeroU16::new).unwrap_or(std::num::NonZeroU16::MIN);
+		let prompt = Prompt::builder().messages(messages).maybe_system(self.system.clone()).model(model).max_tokens(max_tokens);
+		let response = client.message(prompt).await?;
+		trimmed.prepend(format!("Summary of the earlier conversation:\n{}", response.text()));
+		*self = trimmed;
+		Ok(())
+	}
+}
+
+fn is_turn_start(message: &ChatMessage) -> bool {
+	message.role == Role::User && !message.content.blocks().any(|block| matches!(block, Block::ToolResult { .. }))
+}
+
+fn text_tokens(text: &str) -> u32 {
+	u32::try_from(text.len().div_ceil(4)).unwrap_or(u32::MAX)
+}
+
+fn content_tokens(content: &Content) -> u32 {
+	match content {
+		Content::SinglePart(text) => text_tokens(text),
+		Content::MultiPart(blocks) => blocks.iter().map(block_tokens).fold(0, u32::saturating_add),
+	}
+}
+
+fn block_tokens(block: &Block) -> u32 {
+	match block {
+		Block::Text { text, .. } => text_tokens(text),
+		Block::ToolUse { call, .. } => text_tokens(&call.name).saturating_add(text_tokens(&call.input.to_string())),
+		Block::ToolResult { call, .. } => content_tokens(&call.content),
+		Block::Image { .. } | Block::Document { .. } => Conversation::MEDIA_TOKENS,
+		Block::Thinking { thinking, .. } => text_tokens(thinking),
+		Block::RedactedThinking { data } => text_tokens(data),
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversationError {
+	Alternation { expected: Role, found: Role },
+}
+
+impl std::fmt::Display for ConversationError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Alternation { expected, found } => write!(f, "Expected a {expected} message, found a {found} message"),
+		}
+	}
+}
+
+impl std::error::Error for ConversationError {}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+	use {
+		super::*,
+		crate::{AnthropicClient, ResponseMessage, chat_message::Usage, client::AnthropicError, response::StopReason, transport::MockTransport},
+		std::sync::Arc,
+	};
+
+	fn long_conversation() -> Conversation {
+		let mut conversation = Conversation::new();
+		for text in ["Hello", "Hi", "How are you?"] {
+			conversation.push(ChatMessage { role: conversation.next_role(), content: Content::text(text.repeat(100)) }).unwrap();
+		}
+		conversation
+	}
+
+	#[tokio::test]
+	async fn summaries_fit_the_output_limit_of_the_model() {
+		let summary = ResponseMessage {
+			id: "msg_1".to_owned(),
+			message: ChatMessage::assistant("They said hello."),
+			model: Model::Haiku30,
+			stop_reason: Some(StopReason::EndTurn),
+			stop_sequence: None,
+			usage: Usage::default(),
+		};
+		let mock = Arc::new(MockTransport::new().message(&summary));
+		let mut conversation = long_conversation();
+
+		conversation.summarize(&AnthropicClient::with_transport(mock.clone()), Model::Haiku30, 100).await.unwrap();
+		let requests = mock.requests().await;
+		assert_eq!(requests[0].body.as_ref().and_then(|body| body.get("max_tokens")), Some(&serde_json::json!(4096)));
+		assert_eq!(conversation.messages().len(), 1);
+		assert!(conversation.messages()[0].content.blocks().any(|block| matches!(block, Block::Text { text, .. } if text.ends_with("They said hello."))));
+	}
+
+	#[tokio::test]
+	async fn failed_summaries_keep_the_history() {
+		let mock = Arc::new(MockTransport::new().error(AnthropicError::InvalidRequest { message: "bad".to_owned() }));
+		let mut conversation = long_conversation();
+		assert!(conversation.summarize(&AnthropicClient::with_transport(mock), Model::Haiku30, 100).await.is_err());
+		assert_eq!(conversation, long_conversation());
+	}
+}
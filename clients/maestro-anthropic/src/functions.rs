@@ -1,21 +1,60 @@
 use {
-	crate::{chat_message::ChatMessage, Prompt},
+	crate::{Prompt, chat_message::ChatMessage},
 	dioxus::prelude::*,
-	futures::StreamExt,
 	server_fn::codec::{StreamingText, TextStream},
 };
+#[cfg(feature = "server")]
+use {
+	crate::{
+		chat_event::{ChatError, ChatEvent},
+		stream::FilterExt,
+	},
+	futures::{StreamExt, future::Either},
+	tokio::sync::mpsc,
+};
 
+/// Stream the answer text. The first error ends the stream as its last item,
+/// so a truncated answer can be told apart from a complete one. The upstream
+/// request stops when the client disconnects.
 #[server(output = StreamingText)]
 pub async fn stream_chat_from_server(messages: Vec<ChatMessage>, temperature: f32, system_prompt: String) -> Result<TextStream, ServerFnError> {
-	use {crate::stream::FilterExt, futures::pin_mut};
 	let client = crate::extract_anthropic_client().await?;
-	let (tx, rx) = futures::channel::mpsc::unbounded();
+	let stream = client.stream(Prompt::builder().temperature(temperature).system(system_prompt).messages(messages)).await?;
+	Ok(spawn_forward(stream.filter_rate_limit().text().map(|text| text.map_err(ServerFnError::new))))
+}
+
+/// Stream [`ChatEvent`]s as JSON lines, decode them with [`ChatEvent::decode`].
+/// `prompt` carries the model, `max_tokens`, tools and every other setting.
+/// Tool uses are streamed but not executed. The upstream request stops when
+/// the client disconnects.
+#[server(output = StreamingText)]
+pub async fn chat_events_from_server(prompt: Prompt) -> Result<TextStream, ServerFnError> {
+	let client = crate::extract_anthropic_client().await?;
+	let events = match client.stream_chat(prompt).await {
+		Ok(stream) => Either::Left(stream.filter_rate_limit().chat_events()),
+		Err(error) => Either::Right(futures::stream::iter([ChatEvent::Error { error: ChatError::from(&error) }])),
+	};
+	Ok(spawn_forward(events.map(|event| Ok(event.to_line()))))
+}
+
+/// Forward `chunks` to the client from a task that stops, dropping `chunks`
+/// and the request behind it, as soon as the client goes away. The first
+/// error is forwarded and ends the stream.
+#[cfg(feature = "server")]
+fn spawn_forward<S>(chunks: S) -> TextStream
+where
+	S: futures::Stream<Item = Result<String, ServerFnError>> + Send + 'static,
+{
+	let (tx, mut rx) = mpsc::unbounded_channel::<Result<String, ServerFnError>>();
 	tokio::spawn(async move {
-		let stream = client.stream(Prompt::builder().temperature(temperature).system(system_prompt).messages(messages)).await.unwrap().filter_rate_limit().text();
-		pin_mut!(stream);
-		while let Some(Ok(text)) = stream.next().await {
-			let _ = tx.unbounded_send(Ok(text.to_string()));
+		let mut chunks = std::pin::pin!(chunks);
+		let mut closed = std::pin::pin!(tx.closed());
+		while let Either::Right((Some(chunk), _)) = futures::future::select(closed.as_mut(), chunks.next()).await {
+			let failed = chunk.is_err();
+			if tx.send(chunk).is_err() || failed {
+				break;
+			}
 		}
 	});
-	Ok(TextStream::new(rx))
+	TextStream::new(futures::stream::poll_fn(move |cx| rx.poll_recv(cx)))
 }
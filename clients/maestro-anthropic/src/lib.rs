@@ -3,22 +3,45 @@ pub use prompt::Prompt;
 
 pub mod chat_message;
 
+pub mod chat_event;
+
+pub mod conversation;
+pub use conversation::Conversation;
+
+pub mod media;
+
 pub mod model;
 pub use model::Model;
 
 pub mod tool;
-pub use tool::Tool;
+pub use tool::{Tool, TypedTool};
 
 #[cfg(feature = "server")]
 pub mod client;
 #[cfg(feature = "server")]
 pub use client::AnthropicClient;
 
+#[cfg(feature = "server")]
+pub mod agent;
+#[cfg(feature = "server")]
+pub use agent::Agent;
+
 #[cfg(feature = "server")]
 pub mod response;
 #[cfg(feature = "server")]
 pub use response::ResponseMessage;
 
+#[cfg(feature = "server")]
+pub mod usage;
+#[cfg(feature = "server")]
+pub use usage::UsageTracker;
+
+#[cfg(feature = "server")]
+pub mod batch;
+
+#[cfg(feature = "server")]
+pub mod transport;
+
 #[cfg(feature = "server")]
 pub mod stream;
 #[cfg(feature = "server")]
@@ -29,8 +52,8 @@ pub mod from_server;
 #[cfg(all(feature = "server", feature = "dioxus"))]
 pub use from_server::extract_anthropic_client;
 
-#[cfg(feature = "server")]
-pub mod full_client;
-
 #[cfg(feature = "dioxus")]
 pub mod functions;
+
+#[cfg(feature = "dioxus")]
+pub mod chat;
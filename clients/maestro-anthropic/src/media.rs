@@ -0,0 +1,86 @@
+use {
+	base64::{Engine, engine::general_purpose::STANDARD},
+	serde::{Deserialize, Serialize},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum_macros::Display, strum_macros::AsRefStr, strum_macros::EnumIter)]
+pub enum MediaType {
+	#[serde(rename = "image/jpeg")]
+	#[strum(to_string = "image/jpeg")]
+	Jpeg,
+	#[serde(rename = "image/png")]
+	#[strum(to_string = "image/png")]
+	Png,
+	#[serde(rename = "image/gif")]
+	#[strum(to_string = "image/gif")]
+	Gif,
+	#[serde(rename = "image/webp")]
+	#[strum(to_string = "image/webp")]
+	Webp,
+	#[serde(rename = "application/pdf")]
+	#[strum(to_string = "application/pdf")]
+	Pdf,
+}
+
+impl MediaType {
+	/// Detect the media type from the magic number at the start of `bytes`.
+	pub fn detect(bytes: &[u8]) -> Option<Self> {
+		match bytes {
+			[0xFF, 0xD8, 0xFF, ..] => Some(Self::Jpeg),
+			[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some(Self::Png),
+			[b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some(Self::Gif),
+			[b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some(Self::Webp),
+			[b'%', b'P', b'D', b'F', b'-', ..] => Some(Self::Pdf),
+			_ => None,
+		}
+	}
+
+	pub fn is_image(self) -> bool {
+		!matches!(self, Self::Pdf)
+	}
+}
+
+/// Where the API reads an image or a document from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum Source {
+	Base64 { media_type: MediaType, data: String },
+	Url { url: String },
+}
+
+impl Source {
+	/// Encode `bytes` with their detected [`MediaType`].
+	///
+	/// # Errors
+	///
+	/// Fails when the media type is not supported by the API.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, UnsupportedMedia> {
+		let media_type = MediaType::detect(bytes).ok_or(UnsupportedMedia)?;
+		Ok(Self::Base64 { media_type, data: STANDARD.encode(bytes) })
+	}
+
+	pub fn url<U>(url: U) -> Self
+	where
+		U: Into<String>,
+	{
+		Self::Url { url: url.into() }
+	}
+
+	pub fn media_type(&self) -> Option<MediaType> {
+		match self {
+			Self::Base64 { media_type, .. } => Some(*media_type),
+			Self::Url { .. } => None,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsupportedMedia;
+
+impl std::fmt::Display for UnsupportedMedia {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str("unsupported media type, expected JPEG, PNG, GIF, WebP or PDF")
+	}
+}
+
+impl std::error::Error for UnsupportedMedia {}
@@ -1,29 +1,177 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, strum_macros::EnumIter)]
-#[serde(rename_all = "snake_case")]
+/// A Claude model. Known models carry their [`ModelInfo`] from [`CATALOGUE`],
+/// any other model ID goes through [`Model::Custom`].
+///
+/// It serializes as the model ID. Deserializing an ID or an alias of a known
+/// model gives its variant, unknown IDs give [`Model::Custom`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum Model {
-	#[serde(rename = "claude-3-7-sonnet-20250219")]
+	#[default]
+	Sonnet45,
+	Haiku45,
+	Opus41,
+	Opus40,
+	Sonnet40,
 	Sonnet37,
-	#[serde(rename = "claude-3-5-sonnet-latest")]
 	Sonnet35,
 	/// 2024-06-20
-	#[serde(rename = "claude-3-5-sonnet-20240620")]
 	Sonnet35_20240620,
 	/// 2024-10-22
-	#[serde(rename = "claude-3-5-sonnet-20241022")]
 	Sonnet35_20241022,
-	#[serde(rename = "claude-3-opus-latest")]
 	Opus30,
-	#[serde(rename = "claude-3-opus-20240229")]
 	Opus30_20240229,
-	#[serde(rename = "claude-3-sonnet-20240229")]
 	Sonnet30,
-	#[serde(rename = "claude-3-5-haiku-latest")]
 	Haiku35,
-	#[serde(rename = "claude-3-5-haiku-20241022")]
 	Haiku35_20241022,
-	#[default]
-	#[serde(rename = "claude-3-haiku-20240307", alias = "claude-3-haiku-latest")]
 	Haiku30,
+	/// A model ID missing from the catalogue, e.g. a new snapshot. Requests
+	/// using it are not validated against model capabilities.
+	Custom(String),
+}
+
+/// Prices in USD per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Pricing {
+	pub input: f64,
+	pub output: f64,
+	pub cache_write: f64,
+	pub cache_read: f64,
+}
+
+impl Pricing {
+	const fn new(input: f64, output: f64) -> Self {
+		// Cache writes cost 25% more than input tokens, cache reads 90% less.
+		Self { input, output, cache_write: input * 1.25, cache_read: input * 0.1 }
+	}
+}
+
+/// What a model supports and costs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelInfo {
+	pub model: Model,
+	pub id: &'static str,
+	/// Other IDs the API accepts for the same model.
+	pub aliases: &'static [&'static str],
+	/// Input and output tokens the model can attend to.
+	pub context_window: u32,
+	pub max_output_tokens: u32,
+	pub vision: bool,
+	pub tools: bool,
+	pub thinking: bool,
+	pub pricing: Pricing,
+}
+
+impl ModelInfo {
+	const fn new(model: Model, id: &'static str, max_output_tokens: u32, pricing: Pricing) -> Self {
+		Self { model, id, aliases: &[], context_window: 200_000, max_output_tokens, vision: true, tools: true, thinking: false, pricing }
+	}
+
+	const fn aliases(mut self, aliases: &'static [&'static str]) -> Self {
+		self.aliases = aliases;
+		self
+	}
+
+	const fn thinking(mut self) -> Self {
+		self.thinking = true;
+		self
+	}
+}
+
+pub static CATALOGUE: [ModelInfo; 15] = [
+	ModelInfo::new(Model::Sonnet45, "claude-sonnet-4-5-20250929", 64_000, Pricing::new(3.0, 15.0)).aliases(&["claude-sonnet-4-5"]).thinking(),
+	ModelInfo::new(Model::Haiku45, "claude-haiku-4-5-20251001", 64_000, Pricing::new(1.0, 5.0)).aliases(&["claude-haiku-4-5"]).thinking(),
+	ModelInfo::new(Model::Opus41, "claude-opus-4-1-20250805", 32_000, Pricing::new(15.0, 75.0)).aliases(&["claude-opus-4-1"]).thinking(),
+	ModelInfo::new(Model::Opus40, "claude-opus-4-20250514", 32_000, Pricing::new(15.0, 75.0)).aliases(&["claude-opus-4-0"]).thinking(),
+	ModelInfo::new(Model::Sonnet40, "claude-sonnet-4-20250514", 64_000, Pricing::new(3.0, 15.0)).aliases(&["claude-sonnet-4-0"]).thinking(),
+	ModelInfo::new(Model::Sonnet37, "claude-3-7-sonnet-20250219", 64_000, Pricing::new(3.0, 15.0)).aliases(&["claude-3-7-sonnet-latest"]).thinking(),
+	ModelInfo::new(Model::Sonnet35, "claude-3-5-sonnet-latest", 8192, Pricing::new(3.0, 15.0)),
+	ModelInfo::new(Model::Sonnet35_20240620, "claude-3-5-sonnet-20240620", 8192, Pricing::new(3.0, 15.0)),
+	ModelInfo::new(Model::Sonnet35_20241022, "claude-3-5-sonnet-20241022", 8192, Pricing::new(3.0, 15.0)),
+	ModelInfo::new(Model::Opus30, "claude-3-opus-latest", 4096, Pricing::new(15.0, 75.0)),
+	ModelInfo::new(Model::Opus30_20240229, "claude-3-opus-20240229", 4096, Pricing::new(15.0, 75.0)),
+	ModelInfo::new(Model::Sonnet30, "claude-3-sonnet-20240229", 4096, Pricing::new(3.0, 15.0)),
+	ModelInfo::new(Model::Haiku35, "claude-3-5-haiku-latest", 8192, Pricing::new(0.8, 4.0)),
+	ModelInfo::new(Model::Haiku35_20241022, "claude-3-5-haiku-20241022", 8192, Pricing::new(0.8, 4.0)),
+	ModelInfo::new(Model::Haiku30, "claude-3-haiku-20240307", 4096, Pricing::new(0.25, 1.25)).aliases(&["claude-3-haiku-latest"]),
+];
+
+impl Model {
+	/// The catalogue entry of this model. Custom IDs matching a known ID or
+	/// alias resolve to that entry.
+	pub fn info(&self) -> Option<&'static ModelInfo> {
+		match self {
+			Self::Custom(id) => Self::lookup(id),
+			model => CATALOGUE.iter().find(|info| &info.model == model),
+		}
+	}
+
+	pub fn id(&self) -> &str {
+		match self {
+			Self::Custom(id) => id,
+			model => model.info().map_or("", |info| info.id),
+		}
+	}
+
+	pub fn is_custom(&self) -> bool {
+		matches!(self, Self::Custom(_))
+	}
+
+	pub fn pricing(&self) -> Option<Pricing> {
+		self.info().map(|info| info.pricing)
+	}
+
+	/// Input and output tokens the model can attend to, 200k for unknown
+	/// models.
+	pub fn context_window(&self) -> u32 {
+		self.info().map_or(200_000, |info| info.context_window)
+	}
+
+	/// Output tokens the model can generate in a response, 4096 for unknown
+	/// models.
+	pub fn max_output_tokens(&self) -> u32 {
+		self.info().map_or(4096, |info| info.max_output_tokens)
+	}
+
+	pub fn known() -> impl Iterator<Item = &'static Model> {
+		CATALOGUE.iter().map(|info| &info.model)
+	}
+
+	fn lookup(id: &str) -> Option<&'static ModelInfo> {
+		CATALOGUE.iter().find(|info| info.id == id || info.aliases.contains(&id))
+	}
+}
+
+impl From<String> for Model {
+	fn from(id: String) -> Self {
+		match Self::lookup(&id) {
+			Some(info) => info.model.clone(),
+			None => Self::Custom(id),
+		}
+	}
+}
+
+impl From<&str> for Model {
+	fn from(id: &str) -> Self {
+		match Self::lookup(id) {
+			Some(info) => info.model.clone(),
+			None => Self::Custom(id.to_owned()),
+		}
+	}
+}
+
+impl From<Model> for String {
+	fn from(model: Model) -> Self {
+		match model {
+			Model::Custom(id) => id,
+			model => model.id().to_owned(),
+		}
+	}
+}
+
+impl std::fmt::Display for Model {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.id())
+	}
 }
@@ -1,5 +1,9 @@
 use {
-	super::{chat_message::ChatMessage, tool, Model, Tool},
+	super::{
+		Model, Tool,
+		chat_message::{Block, ChatMessage},
+		tool,
+	},
 	serde::{Deserialize, Serialize},
 	std::num::NonZeroU16,
 };
@@ -10,14 +14,20 @@ pub struct Prompt {
 	pub stream: bool,
 
 	pub messages: Vec<ChatMessage>,
-	#[builder(default = Model::Sonnet37)]
+	#[builder(default)]
 	pub model: Model,
 	#[builder(default = NonZeroU16::new(8192).unwrap())]
 	pub max_tokens: NonZeroU16,
 	#[builder(default = 0.2)]
 	pub temperature: f32,
+	#[builder(into)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub system: Option<System>,
+	/// Extended thinking. The API requires `temperature` to be 1.0 and
+	/// `max_tokens` to exceed the budget when it is enabled, see
+	/// [`Prompt::validate`].
 	#[serde(skip_serializing_if = "Option::is_none")]
-	pub system: Option<String>,
+	pub thinking: Option<Thinking>,
 
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub top_k: Option<NonZeroU16>,
@@ -37,3 +47,191 @@ pub struct Prompt {
 }
 
 pub use prompt_builder::*;
+
+impl Prompt {
+	/// The smallest thinking budget the API accepts.
+	pub const MIN_THINKING_BUDGET: u32 = 1024;
+
+	/// Check the prompt against the capabilities of its model. Models missing
+	/// from the [`crate::model::CATALOGUE`] are only checked for settings
+	/// every model rejects.
+	///
+	/// # Errors
+	///
+	/// Fails on the first setting the API would reject.
+	pub fn validate(&self) -> Result<(), PromptError> {
+		if self.messages.is_empty() {
+			return Err(PromptError::NoMessages);
+		}
+		let thinking = match self.thinking {
+			Some(Thinking::Enabled { budget_tokens }) => Some(budget_tokens),
+			_ => None,
+		};
+		if let Some(budget_tokens) = thinking {
+			if budget_tokens < Self::MIN_THINKING_BUDGET || budget_tokens >= u32::from(self.max_tokens.get()) {
+				return Err(PromptError::ThinkingBudget { budget_tokens, max_tokens: self.max_tokens });
+			}
+			#[allow(clippy::float_cmp)]
+			if self.temperature != 1.0 {
+				return Err(PromptError::ThinkingTemperature { temperature: self.temperature });
+			}
+		}
+		let Some(info) = self.model.info() else {
+			return Ok(());
+		};
+		let unsupported = |feature| Err(PromptError::Unsupported { model: self.model.clone(), feature });
+		if u32::from(self.max_tokens.get()) > info.max_output_tokens {
+			return Err(PromptError::MaxTokens { model: self.model.clone(), max: info.max_output_tokens, requested: self.max_tokens });
+		}
+		if thinking.is_some() && !info.thinking {
+			return unsupported(Feature::Thinking);
+		}
+		if !self.tools.is_empty() && !info.tools {
+			return unsupported(Feature::Tools);
+		}
+		let media = self.messages.iter().flat_map(|message| message.content.blocks()).any(|block| matches!(block, Block::Image { .. } | Block::Document { .. }));
+		if media && !info.vision {
+			return unsupported(Feature::Vision);
+		}
+		Ok(())
+	}
+}
+
+/// The system prompt, either plain text or blocks so parts of it can be
+/// cached.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, derive_more::IsVariant)]
+#[serde(untagged)]
+pub enum System {
+	Text(String),
+	Blocks(Vec<Block>),
+}
+
+impl System {
+	/// Set a cache breakpoint at the end of the system prompt.
+	#[must_use]
+	pub fn cached(self) -> Self {
+		let mut blocks = match self {
+			Self::Text(text) => vec![Block::text(text)],
+			Self::Blocks(blocks) => blocks,
+		};
+		if let Some(last) = blocks.pop() {
+			blocks.push(last.cached());
+		}
+		Self::Blocks(blocks)
+	}
+}
+
+impl From<String> for System {
+	fn from(text: String) -> Self {
+		Self::Text(text)
+	}
+}
+
+impl From<&str> for System {
+	fn from(text: &str) -> Self {
+		Self::Text(text.to_owned())
+	}
+}
+
+impl From<Vec<Block>> for System {
+	fn from(blocks: Vec<Block>) -> Self {
+		Self::Blocks(blocks)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum Thinking {
+	/// `budget_tokens` must be at least 1024.
+	Enabled {
+		budget_tokens: u32,
+	},
+	Disabled,
+}
+
+impl Thinking {
+	pub const fn enabled(budget_tokens: u32) -> Self {
+		Self::Enabled { budget_tokens }
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum_macros::Display)]
+pub enum Feature {
+	#[strum(serialize = "images and documents")]
+	Vision,
+	#[strum(serialize = "tools")]
+	Tools,
+	#[strum(serialize = "extended thinking")]
+	Thinking,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptError {
+	NoMessages,
+	MaxTokens { model: Model, max: u32, requested: NonZeroU16 },
+	Unsupported { model: Model, feature: Feature },
+	ThinkingBudget { budget_tokens: u32, max_tokens: NonZeroU16 },
+	ThinkingTemperature { temperature: f32 },
+}
+
+impl std::fmt::Display for PromptError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::NoMessages => write!(f, "a prompt needs at least one message"),
+			Self::MaxTokens { model, max, requested } => write!(f, "`{model}` outputs at most {max} tokens but {requested} were requested"),
+			Self::Unsupported { model, feature } => write!(f, "`{model}` does not support {feature}"),
+			Self::ThinkingBudget { budget_tokens, max_tokens } => {
+				write!(f, "the thinking budget must be at least {} and less than `max_tokens` ({max_tokens}), got {budget_tokens}", Prompt::MIN_THINKING_BUDGET)
+			},
+			Self::ThinkingTemperature { temperature } => write!(f, "extended thinking requires a temperature of 1.0, got {temperature}"),
+		}
+	}
+}
+
+impl std::error::Error for PromptError {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn prompt(model: Model, max_tokens: u16, thinking: Option<u32>) -> Prompt {
+		let mut prompt = Prompt::builder().messages(vec![ChatMessage::user("Hi")]).model(model).max_tokens(NonZeroU16::new(max_tokens).unwrap()).build(false);
+		if let Some(budget_tokens) = thinking {
+			prompt.thinking = Some(Thinking::Enabled { budget_tokens });
+			prompt.temperature = 1.0;
+		}
+		prompt
+	}
+
+	#[test]
+	fn thinking_needs_a_thinking_model() {
+		let error = prompt(Model::Haiku35, 8192, Some(2048)).validate().unwrap_err();
+		assert_eq!(error, PromptError::Unsupported { model: Model::Haiku35, feature: Feature::Thinking });
+		assert_eq!(error.to_string(), "`claude-3-5-haiku-latest` does not support extended thinking");
+		assert_eq!(prompt(Model::Sonnet45, 8192, Some(2048)).validate(), Ok(()));
+	}
+
+	#[test]
+	fn max_tokens_fit_the_model() {
+		let error = prompt(Model::Haiku30, 8192, None).validate().unwrap_err();
+		assert_eq!(error, PromptError::MaxTokens { model: Model::Haiku30, max: 4096, requested: NonZeroU16::new(8192).unwrap() });
+		assert_eq!(error.to_string(), "`claude-3-haiku-20240307` outputs at most 4096 tokens but 8192 were requested");
+		assert_eq!(prompt(Model::Haiku30, 4096, None).validate(), Ok(()));
+	}
+
+	#[test]
+	fn custom_models_pass_through() {
+		assert_eq!(prompt(Model::Custom("claude-next-1".to_owned()), u16::MAX, Some(2048)).validate(), Ok(()));
+		// Known IDs are still checked.
+		assert!(matches!(prompt(Model::Custom("claude-3-haiku-latest".to_owned()), 8192, None).validate(), Err(PromptError::MaxTokens { max: 4096, .. })));
+	}
+
+	#[test]
+	fn thinking_settings_apply_to_every_model() {
+		let custom = || Model::Custom("claude-next-1".to_owned());
+		let budget = PromptError::ThinkingBudget { budget_tokens: 512, max_tokens: NonZeroU16::new(8192).unwrap() };
+		assert_eq!(prompt(custom(), 8192, Some(512)).validate(), Err(budget));
+		let temperature = Prompt { temperature: 0.2, ..prompt(custom(), 8192, Some(2048)) };
+		assert_eq!(temperature.validate(), Err(PromptError::ThinkingTemperature { temperature: 0.2 }));
+	}
+}
@@ -1,158 +1,56 @@
 use {
 	crate::{
-		stream::{ContentMismatch, Delta, DeltaError, MessageStats},
 		Model,
+		stream::{ContentMismatch, Delta, DeltaError, MessageStats},
 	},
 	serde::{Deserialize, Serialize},
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, strum_macros::Display, strum_macros::AsRefStr)]
-#[serde(rename_all = "snake_case")]
-pub enum Role {
-	User,
-	Assistant,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Message {
-	pub role: Role,
-	pub content: Content,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, derive_more::IsVariant)]
-#[serde(rename_all = "snake_case")]
-#[serde(untagged)]
-pub enum Content {
-	SinglePart(String),
-	MultiPart(Vec<Block>),
-}
-
-impl Content {
-	pub fn text<T>(text: T) -> Self
-	where
-		T: Into<String>,
-	{
-		Self::SinglePart(text.into())
-	}
-
-	pub fn unwrap_single_part(self) -> Block {
-		match self {
-			Self::SinglePart(text) => Block::Text { text },
-			Self::MultiPart(_) => {
-				panic!("Content is MultiPart, not SinglePart");
-			},
-		}
-	}
-
-	pub fn push<P>(&mut self, part: P)
-	where
-		P: Into<Block>,
-	{
-		if self.is_single_part() {
-			let mut old = Content::MultiPart(vec![]);
-			std::mem::swap(self, &mut old);
-			self.push(old.unwrap_single_part());
-		}
-
-		if let Content::MultiPart(parts) = self {
-			parts.push(part.into());
-		}
-	}
-
-	pub fn last(&self) -> Option<&Block> {
-		match self {
-			Self::SinglePart(_) => None,
-			Self::MultiPart(parts) => parts.last(),
-		}
-	}
-}
-
-impl std::fmt::Display for Content {
-	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-		match self {
-			Self::SinglePart(string) => write!(f, "{}", string),
-			// This could be derived but the `Join` trait is not stable. Neither
-			// is `Iterator::intersperse`. This also has fewer allocations.
-			Self::MultiPart(parts) => {
-				let mut iter = parts.iter();
-				if let Some(part) = iter.next() {
-					write!(f, "{}", part)?;
-					for part in iter {
-						write!(f, "{}{}", Self::SEP, part)?;
-					}
-				}
-				Ok(())
-			},
-		}
-	}
-}
-
-impl Content {
-	pub const SEP: &'static str = "\n\n";
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, strum_macros::Display)]
-#[serde(rename_all = "snake_case")]
-#[serde(tag = "type")]
-pub enum Block {
-	#[serde(alias = "text_delta")]
-	Text { text: String },
-	ToolUse {
-		#[serde(flatten)]
-		call: Use,
-	},
-	ToolResult {
-		#[serde(flatten)]
-		call: ToolBlock,
-	},
-}
+pub use crate::chat_message::{Block, ChatMessage as Message, Content, Role, StopReason, ToolBlock, Usage, Use};
 
 impl Block {
-	pub fn text<T>(text: T) -> Self
-	where
-		T: Into<String>,
-	{
-		Self::Text { text: text.into() }
-	}
-
+	/// Apply `deltas` to this block. JSON deltas are concatenated and parsed
+	/// once, so every JSON delta of a block must be passed in the same call.
+	/// [`crate::stream::Accumulator`] takes care of that for a whole stream.
+	///
+	/// # Errors
+	///
+	/// Fails when a delta does not match the block or the JSON is invalid.
 	pub fn merge_deltas<TDeltas>(&mut self, deltas: TDeltas) -> Result<(), DeltaError>
 	where
 		TDeltas: IntoIterator<Item = Delta>,
 	{
-		let mut it = deltas.into_iter();
-		let acc: Delta = match it.next() {
-			Some(delta) => delta,
-			None => return Ok(()),
-		};
-		let acc: Delta = it.try_fold(acc, |acc, delta| acc.merge(delta))?;
-		match (self, acc) {
-			(Block::Text { text, .. }, Delta::Text { text: delta }) => {
-				text.push_str(&delta);
-			},
-			(Block::ToolUse { call: Use { input, .. } }, Delta::Json { partial_json }) => {
-				use serde_json::Value::Object;
-				let partial_json: serde_json::Value = serde_json::from_str(&partial_json)
-					.map_err(|e| DeltaError::Parse { error: format!("Could not merge partial json `{}` into `{}` because {}", partial_json, input, e) })?;
-				if let (Object(new), Object(old)) = (partial_json, input) {
-					old.extend(new);
-				}
-			},
-			(this, acc) => {
-				let variant_name = match this {
-					Block::Text { .. } => stringify!(Block::Text),
-					Block::ToolUse { .. } => stringify!(Block::ToolUse),
-					Block::ToolResult { .. } => stringify!(Block::ToolResult),
-				};
-				return Err(ContentMismatch { from: acc, to: variant_name }.into());
-			},
+		let mut json = String::new();
+		for delta in deltas {
+			match (&mut *self, delta) {
+				(Block::Text { text, .. }, Delta::Text { text: delta }) => text.push_str(&delta),
+				(Block::Thinking { thinking, .. }, Delta::Thinking { thinking: delta }) => thinking.push_str(&delta),
+				(Block::Thinking { signature, .. }, Delta::Signature { signature: delta }) => signature.push_str(&delta),
+				(Block::ToolUse { .. }, Delta::Json { partial_json }) => json.push_str(&partial_json),
+				(this, delta) => return Err(ContentMismatch { from: delta, to: this.variant_name() }.into()),
+			}
+		}
+		// Tools without arguments stream a single empty delta.
+		if let (Block::ToolUse { call: Use { input, .. }, .. }, false) = (self, json.trim().is_empty()) {
+			use serde_json::Value::Object;
+			let partial_json: serde_json::Value = serde_json::from_str(&json)
+				.map_err(|e| DeltaError::Parse { error: format!("Could not merge partial json `{}` into `{}` because {}", json, input, e) })?;
+			if let (Object(new), Object(old)) = (partial_json, input) {
+				old.extend(new);
+			}
 		}
 		Ok(())
 	}
 
-	pub fn tool_use(&self) -> Option<&Use> {
+	fn variant_name(&self) -> &'static str {
 		match self {
-			Self::ToolUse { call, .. } => Some(call),
-			_ => None,
+			Block::Text { .. } => stringify!(Block::Text),
+			Block::ToolUse { .. } => stringify!(Block::ToolUse),
+			Block::ToolResult { .. } => stringify!(Block::ToolResult),
+			Block::Image { .. } => stringify!(Block::Image),
+			Block::Document { .. } => stringify!(Block::Document),
+			Block::Thinking { .. } => stringify!(Block::Thinking),
+			Block::RedactedThinking { .. } => stringify!(Block::RedactedThinking),
 		}
 	}
 }
@@ -174,10 +72,23 @@ impl ResponseMessage {
 
 		self.stop_sequence = delta.stop_sequence;
 		if let Some(usage) = delta.usage {
-			self.usage = usage;
+			self.usage.apply_delta(&usage);
 		}
 	}
 
+	/// The final answer, without thinking blocks.
+	pub fn text(&self) -> String {
+		self.message.content.plain_text()
+	}
+
+	/// The content of the thinking blocks.
+	pub fn thinking(&self) -> impl Iterator<Item = &str> {
+		self.message.content.blocks().filter_map(|block| match block {
+			Block::Thinking { thinking, .. } => Some(thinking.as_str()),
+			_ => None,
+		})
+	}
+
 	pub fn tool_use(&self) -> Option<&Use> {
 		if !matches!(self.stop_reason, Some(StopReason::ToolUse)) {
 			return None;
@@ -186,32 +97,3 @@ impl ResponseMessage {
 		self.message.content.last()?.tool_use()
 	}
 }
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum StopReason {
-	EndTurn,
-	MaxTokens,
-	StopSequence,
-	ToolUse,
-}
-
-#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Usage {
-	pub input_tokens: u64,
-	pub output_tokens: u64,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Use {
-	pub id: String,
-	pub name: String,
-	pub input: serde_json::Value,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct ToolBlock {
-	pub tool_use_id: String,
-	pub content: Content,
-	pub is_error: bool,
-}
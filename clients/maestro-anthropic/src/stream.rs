@@ -1,22 +1,36 @@
 use {
 	super::{
+		chat_event::{ChatEvent, ChatEventEncoder},
 		client::AnthropicError,
-		response::{Block, ResponseMessage, StopReason, Usage},
+		response::{Block, Content, ResponseMessage, StopReason, Usage},
 	},
-	futures::StreamExt,
+	futures::{StreamExt, future::ready},
 	serde::{Deserialize, Serialize},
-	std::{pin::Pin, task::Poll},
+	std::{collections::BTreeMap, pin::Pin, task::Poll},
 };
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum Event {
 	Ping,
-	MessageStart { message: ResponseMessage },
-	ContentBlockStart { index: usize, content_block: Block },
-	ContentBlockDelta { index: usize, delta: Delta },
-	ContentBlockStop { index: usize },
-	MessageStats { delta: MessageStats },
+	MessageStart {
+		message: ResponseMessage,
+	},
+	ContentBlockStart {
+		index: usize,
+		content_block: Block,
+	},
+	ContentBlockDelta {
+		index: usize,
+		delta: Delta,
+	},
+	ContentBlockStop {
+		index: usize,
+	},
+	#[serde(rename = "message_delta")]
+	MessageStats {
+		delta: MessageStats,
+	},
 	MessageStop,
 }
 
@@ -40,6 +54,12 @@ pub enum Delta {
 
 	#[serde(rename = "input_json_delta")]
 	Json { partial_json: String },
+
+	#[serde(rename = "thinking_delta")]
+	Thinking { thinking: String },
+
+	#[serde(rename = "signature_delta")]
+	Signature { signature: String },
 }
 
 #[derive(Debug, thiserror::Error, Serialize)]
@@ -75,12 +95,20 @@ impl Delta {
 			(Delta::Json { partial_json }, Delta::Json { partial_json: delta }) => {
 				partial_json.push_str(&delta);
 			},
+			(Delta::Thinking { thinking }, Delta::Thinking { thinking: delta }) => {
+				thinking.push_str(&delta);
+			},
+			(Delta::Signature { signature }, Delta::Signature { signature: delta }) => {
+				signature.push_str(&delta);
+			},
 			(to, from) => {
 				return Err(ContentMismatch {
 					from,
 					to: match to {
 						Delta::Text { .. } => stringify!(Delta::Text),
 						Delta::Json { .. } => stringify!(Delta::Json),
+						Delta::Thinking { .. } => stringify!(Delta::Thinking),
+						Delta::Signature { .. } => stringify!(Delta::Signature),
 					},
 				});
 			},
@@ -111,6 +139,15 @@ pub enum Error {
 	Parse { error: serde_json::Error, event: eventsource_stream::Event },
 	#[error("API error: {error}")]
 	Anthropic { error: AnthropicError, event: eventsource_stream::Event },
+	#[error("{error}")]
+	Delta {
+		#[from]
+		error: DeltaError,
+	},
+	#[error("Received an event before `message_start`")]
+	NotStarted,
+	#[error("The stream ended before `message_stop`")]
+	Incomplete,
 }
 
 pub struct Stream {
@@ -172,7 +209,45 @@ pub trait FilterExt: futures::stream::Stream<Item = Result<Event, Error>> + Size
 		})
 	}
 
-	/// Filter out everything but text pieces.
+	/// Fold the events into a [`ResponseMessage`], yielding a
+	/// [`Snapshot::Partial`] every time the message visibly changes and a
+	/// [`Snapshot::Complete`] on [`Event::MessageStop`]. See [`Accumulator`].
+	///
+	/// Every snapshot is a copy of the message so far, use an [`Accumulator`]
+	/// directly to only read the message when needed.
+	fn accumulate(self) -> impl futures::Stream<Item = Result<Snapshot, Error>> + Send {
+		self
+			.scan(Accumulator::default(), |accumulator, event| {
+				let snapshot = match event.and_then(|event| accumulator.apply(event)) {
+					Ok(progress) => progress.zip(accumulator.message().cloned()).map(|(progress, message)| Ok(progress.snapshot(message))),
+					Err(error) => Some(Err(error)),
+				};
+				ready(Some(snapshot))
+			})
+			.filter_map(ready)
+	}
+
+	/// Turn the events into [`ChatEvent`]s, see [`ChatEventEncoder`]. Errors
+	/// are yielded as [`ChatEvent::Error`].
+	fn chat_events(self) -> impl futures::Stream<Item = ChatEvent> + Send {
+		self.scan(ChatEventEncoder::default(), |encoder, event| ready(Some(futures::stream::iter(encoder.apply(event))))).flatten()
+	}
+
+	/// Consume the stream and return the complete [`ResponseMessage`].
+	fn final_message(self) -> impl Future<Output = Result<ResponseMessage, Error>> + Send {
+		async move {
+			let mut accumulator = Accumulator::default();
+			let mut stream = std::pin::pin!(self);
+			while let Some(event) = stream.next().await {
+				if accumulator.apply(event?)? == Some(Progress::Complete) {
+					return accumulator.into_message().ok_or(Error::Incomplete);
+				}
+			}
+			Err(Error::Incomplete)
+		}
+	}
+
+	/// Filter out everything but text pieces. Thinking is excluded.
 	fn text(self) -> impl futures::Stream<Item = Result<String, Error>> + Send {
 		self.deltas().filter_map(|result| async move {
 			match result {
@@ -181,6 +256,176 @@ pub trait FilterExt: futures::stream::Stream<Item = Result<Event, Error>> + Size
 			}
 		})
 	}
+
+	/// Filter out everything but thinking pieces.
+	fn thinking(self) -> impl futures::Stream<Item = Result<String, Error>> + Send {
+		self.deltas().filter_map(|result| async move {
+			match result {
+				Ok(Delta::Thinking { thinking }) => Some(Ok(thinking)),
+				_ => None,
+			}
+		})
+	}
+
+	/// Filter out everything but text and thinking pieces, keeping them apart.
+	/// Errors are kept.
+	fn text_and_thinking(self) -> impl futures::Stream<Item = Result<Fragment, Error>> + Send {
+		self.filter_map(|result| async move {
+			match result {
+				Ok(Event::ContentBlockDelta { delta: Delta::Text { text }, .. }) => Some(Ok(Fragment::Text(text))),
+				Ok(Event::ContentBlockDelta { delta: Delta::Thinking { thinking }, .. }) => Some(Ok(Fragment::Thinking(thinking))),
+				Ok(_) => None,
+				Err(error) => Some(Err(error)),
+			}
+		})
+	}
 }
 
 impl<S> FilterExt for S where S: futures::Stream<Item = Result<Event, Error>> + Send {}
+
+/// A piece of streamed text, either part of the answer or of the thinking.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::IsVariant)]
+pub enum Fragment {
+	Text(String),
+	Thinking(String),
+}
+
+/// A view of the message being streamed.
+#[derive(Debug, Clone, PartialEq, derive_more::IsVariant)]
+pub enum Snapshot {
+	/// Text blocks are up to date, tool inputs are filled in once their block
+	/// is closed.
+	Partial(ResponseMessage),
+	Complete(ResponseMessage),
+}
+
+/// How an event changed the message of an [`Accumulator`], read with
+/// [`Accumulator::message`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::IsVariant)]
+pub enum Progress {
+	/// See [`Snapshot::Partial`].
+	Partial,
+	/// See [`Snapshot::Complete`].
+	Complete,
+}
+
+impl Progress {
+	/// Pair the accumulated `message` with this progress.
+	pub fn snapshot(self, message: ResponseMessage) -> Snapshot {
+		match self {
+			Self::Partial => Snapshot::Partial(message),
+			Self::Complete => Snapshot::Complete(message),
+		}
+	}
+}
+
+impl Snapshot {
+	pub fn message(&self) -> &ResponseMessage {
+		match self {
+			Self::Partial(message) | Self::Complete(message) => message,
+		}
+	}
+
+	pub fn into_message(self) -> ResponseMessage {
+		match self {
+			Self::Partial(message) | Self::Complete(message) => message,
+		}
+	}
+}
+
+/// Rebuilds a [`ResponseMessage`] from [`Event`]s. Text deltas are applied as
+/// they arrive, JSON deltas are buffered per content block index and parsed
+/// once the block stops because partial JSON is not valid JSON.
+#[derive(Debug, Default)]
+pub struct Accumulator {
+	message: Option<ResponseMessage>,
+	json: BTreeMap<usize, String>,
+}
+
+impl Accumulator {
+	pub fn message(&self) -> Option<&ResponseMessage> {
+		self.message.as_ref()
+	}
+
+	pub fn into_message(self) -> Option<ResponseMessage> {
+		self.message
+	}
+
+	/// Apply `event` and tell whether the message visibly changed.
+	///
+	/// # Errors
+	///
+	/// Fails if `event` arrives before [`Event::MessageStart`] or does not fit
+	/// the content block it targets.
+	pub fn apply(&mut self, event: Event) -> Result<Option<Progress>, Error> {
+		match (&mut self.message, event) {
+			(_, Event::Ping) => return Ok(None),
+			(message, Event::MessageStart { message: start }) => {
+				self.json.clear();
+				*message = Some(start);
+			},
+			(None, _) => return Err(Error::NotStarted),
+			(Some(message), Event::ContentBlockStart { index, content_block }) => {
+				let parts = Self::parts(message);
+				match index.cmp(&parts.len()) {
+					std::cmp::Ordering::Less => parts[index] = content_block,
+					std::cmp::Ordering::Equal => parts.push(content_block),
+					std::cmp::Ordering::Greater => return Err(DeltaError::from(OutOfBounds { index, max: parts.len() }).into()),
+				}
+			},
+			(Some(_), Event::ContentBlockDelta { index, delta: Delta::Json { partial_json } }) => {
+				self.json.entry(index).or_default().push_str(&partial_json);
+				return Ok(None);
+			},
+			(Some(message), Event::ContentBlockDelta { index, delta }) => {
+				Self::block(message, index)?.merge_deltas([delta])?;
+			},
+			(Some(message), Event::ContentBlockStop { index }) => {
+				let Some(partial_json) = self.json.remove(&index) else {
+					return Ok(None);
+				};
+				Self::block(message, index)?.merge_deltas([Delta::Json { partial_json }])?;
+			},
+			(Some(message), Event::MessageStats { delta }) => message.apply_delta(delta),
+			(Some(_), Event::MessageStop) => return Ok(Some(Progress::Complete)),
+		}
+		Ok(Some(Progress::Partial))
+	}
+
+	fn parts(message: &mut ResponseMessage) -> &mut Vec<Block> {
+		if let Content::SinglePart(text) = &mut message.message.content {
+			let text = std::mem::take(text);
+			message.message.content = Content::MultiPart(if text.is_empty() { vec![] } else { vec![Block::text(text)] });
+		}
+		match &mut message.message.content {
+			Content::MultiPart(parts) => parts,
+			Content::SinglePart(_) => unreachable!("single part content was converted above"),
+		}
+	}
+
+	fn block(message: &mut ResponseMessage, index: usize) -> Result<&mut Block, DeltaError> {
+		let parts = Self::parts(message);
+		let max = parts.len().saturating_sub(1);
+		parts.get_mut(index).ok_or(OutOfBounds { index, max }.into())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn fragments_keep_errors() {
+		let events = [
+			Ok(Event::ContentBlockDelta { index: 0, delta: Delta::Thinking { thinking: "Hmm".to_owned() } }),
+			Ok(Event::ContentBlockDelta { index: 1, delta: Delta::Text { text: "Hi".to_owned() } }),
+			Ok(Event::Ping),
+			Err(Error::Incomplete),
+		];
+		let fragments = futures::stream::iter(events).text_and_thinking().collect::<Vec<_>>().await;
+		assert!(matches!(
+			fragments.as_slice(),
+			[Ok(Fragment::Thinking(thinking)), Ok(Fragment::Text(text)), Err(Error::Incomplete)] if thinking == "Hmm" && text == "Hi"
+		));
+	}
+}
@@ -1,4 +1,7 @@
-use serde::{Deserialize, Serialize};
+use {
+	crate::chat_message::{CacheControl, Use},
+	serde::{Deserialize, Serialize, de::DeserializeOwned},
+};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case", tag = "type")]
@@ -13,4 +16,128 @@ pub struct Tool {
 	pub name: String,
 	pub description: String,
 	pub input_schema: serde_json::Value,
+	/// Set on the last tool to cache every tool definition.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub cache_control: Option<CacheControl>,
+}
+
+impl Tool {
+	#[must_use]
+	pub fn cached(mut self) -> Self {
+		self.cache_control = Some(CacheControl::Ephemeral);
+		self
+	}
+
+	/// Check that `input_schema` describes an object, the only tool input the
+	/// API accepts.
+	///
+	/// # Errors
+	///
+	/// Fails when the root schema does not have `"type": "object"`, e.g. for a
+	/// [`TypedTool`] derived from a `String` or a `Vec`.
+	pub fn check_input_schema(&self) -> Result<(), InputSchemaError> {
+		match self.input_schema.get("type") {
+			Some(serde_json::Value::String(kind)) if kind == "object" => Ok(()),
+			_ => Err(InputSchemaError { tool: self.name.clone(), schema: self.input_schema.clone() }),
+		}
+	}
+}
+
+/// The input schema of a [`Tool`] is not an object, see
+/// [`Tool::check_input_schema`].
+#[derive(Debug)]
+pub struct InputSchemaError {
+	pub tool: String,
+	pub schema: serde_json::Value,
+}
+
+impl std::fmt::Display for InputSchemaError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "the input schema of `{}` must have `\"type\": \"object\"` (received `{}`)", self.tool, self.schema)
+	}
+}
+
+impl std::error::Error for InputSchemaError {}
+
+/// A Rust type used as the input of a [`Tool`]. Implemented for every type
+/// deriving [`schemars::JsonSchema`] and [`serde::Deserialize`]: the tool name
+/// is the schema name (override it with `#[schemars(rename = "...")]`) and the
+/// description is the type's doc comment.
+pub trait TypedTool: Sized {
+	fn tool() -> Tool;
+
+	/// Decode the input of a [`Use`] returned by the model.
+	///
+	/// # Errors
+	///
+	/// Fails when `call` targets another tool or when its input does not match
+	/// `Self`.
+	fn decode(call: &Use) -> Result<Self, ToolInputError>;
+}
+
+impl<T> TypedTool for T
+where
+	T: schemars::JsonSchema + DeserializeOwned,
+{
+	fn tool() -> Tool {
+		let mut input_schema = schemars::schema_for!(T).to_value();
+		let mut description = None;
+		if let Some(schema) = input_schema.as_object_mut() {
+			schema.remove("$schema");
+			schema.remove("title");
+			description = schema.remove("description");
+		}
+		Tool {
+			name: T::schema_name().into(),
+			description: description.and_then(|description| description.as_str().map(str::to_owned)).unwrap_or_default(),
+			input_schema,
+			cache_control: None,
+		}
+	}
+
+	fn decode(call: &Use) -> Result<Self, ToolInputError> {
+		let expected = T::schema_name();
+		if call.name != expected {
+			return Err(ToolInputError::WrongTool { expected: expected.into(), found: call.name.clone() });
+		}
+		serde_json::from_value(call.input.clone()).map_err(|error| ToolInputError::Invalid { tool: call.name.clone(), input: call.input.clone(), error })
+	}
+}
+
+impl Use {
+	/// Shorthand for [`TypedTool::decode`].
+	///
+	/// # Errors
+	///
+	/// See [`TypedTool::decode`].
+	pub fn decode<T>(&self) -> Result<T, ToolInputError>
+	where
+		T: TypedTool,
+	{
+		T::decode(self)
+	}
+}
+
+#[derive(Debug)]
+pub enum ToolInputError {
+	WrongTool { expected: String, found: String },
+	Invalid { tool: String, input: serde_json::Value, error: serde_json::Error },
+}
+
+impl std::fmt::Display for ToolInputError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::WrongTool { expected, found } => write!(f, "expected a call to `{expected}` but the model called `{found}`"),
+			Self::Invalid { tool, input, error } => write!(f, "invalid input for `{tool}`: {error} (received `{input}`)"),
+		}
+	}
+}
+
+impl std::error::Error for ToolInputError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::WrongTool { .. } => None,
+			Self::Invalid { error, .. } => Some(error),
+		}
+	}
 }
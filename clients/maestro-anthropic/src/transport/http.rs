@@ -0,0 +1,88 @@
+use {
+	super::{HttpMethod, HttpRequest, HttpResponse, Transport},
+	crate::{
+		AnthropicClient,
+		client::{AnthropicClientError, AnthropicResult},
+	},
+	futures::{StreamExt, future::BoxFuture},
+	reqwest::{
+		Client, Method,
+		header::{CONTENT_TYPE, HeaderMap, HeaderValue, RETRY_AFTER},
+	},
+	std::{env, time::Duration},
+	zeroize::Zeroizing,
+};
+
+/// Sends requests to the API, or to a proxy at another base URL.
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+	client: Client,
+	base_url: String,
+}
+
+#[bon::bon]
+impl ReqwestTransport {
+	/// `key` defaults to [`AnthropicClient::API_KEY_VAR`] and `base_url` to
+	/// [`AnthropicClient::DEFAULT_BASE_URL`]. `timeout` bounds the time
+	/// between two chunks of a response.
+	///
+	/// # Errors
+	///
+	/// Fails when no key is found, the key is not a valid header value or the
+	/// HTTP client cannot be built.
+	#[builder]
+	pub fn new(
+		#[builder(into)] key: Option<String>,
+		#[builder(into, default = AnthropicClient::DEFAULT_BASE_URL)] base_url: String,
+		timeout: Option<Duration>,
+	) -> AnthropicResult<Self> {
+		let key = match key {
+			Some(key) => Zeroizing::new(key),
+			None => Zeroizing::new(env::var(AnthropicClient::API_KEY_VAR).map_err(|_| AnthropicClientError::MissingKey)?),
+		};
+		let mut val = HeaderValue::from_str(&key)?;
+		val.set_sensitive(true);
+		let mut headers = HeaderMap::new();
+		headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+		headers.insert("anthropic-version", HeaderValue::from_static(AnthropicClient::ANTHROPIC_VERSION));
+		headers.insert("x-api-key", val);
+		let mut client = Client::builder().default_headers(headers).user_agent(AnthropicClient::USER_AGENT);
+		if let Some(timeout) = timeout {
+			client = client.read_timeout(timeout);
+		}
+		Ok(Self { client: client.build()?, base_url: base_url.trim_end_matches('/').to_owned() })
+	}
+
+	pub fn url(&self, path: &str) -> String {
+		format!("{}{path}", self.base_url)
+	}
+}
+
+impl Transport for ReqwestTransport {
+	fn send(&self, request: HttpRequest) -> BoxFuture<'_, AnthropicResult<HttpResponse>> {
+		Box::pin(async move {
+			let method = match request.method {
+				HttpMethod::Get => Method::GET,
+				HttpMethod::Post => Method::POST,
+			};
+			let mut builder = self.client.request(method, self.url(&request.path));
+			if !request.query.is_empty() {
+				builder = builder.query(&request.query);
+			}
+			if let Some(body) = &request.body {
+				builder = builder.json(body);
+			}
+			if let Some(timeout) = request.timeout {
+				builder = builder.timeout(timeout);
+			}
+			let response = builder.send().await?;
+			let retry_after = response
+				.headers()
+				.get(RETRY_AFTER)
+				.and_then(|value| value.to_str().ok())
+				.and_then(|value| value.trim().parse::<f64>().ok())
+				.and_then(|seconds| Duration::try_from_secs_f64(seconds).ok());
+			Ok(HttpResponse { status: response.status().as_u16(), retry_after, body: response.bytes_stream().boxed() })
+		})
+	}
+}
@@ -0,0 +1,276 @@
+use {
+	super::{HttpRequest, HttpResponse, Transport},
+	crate::{
+		chat_message::{Block, Content, Usage, Use},
+		client::{AnthropicClientError, AnthropicError, AnthropicResult},
+		response::ResponseMessage,
+		stream::{Delta, Event, MessageStats},
+	},
+	bytes::Bytes,
+	futures::future::BoxFuture,
+	serde::Serialize,
+	std::{
+		sync::atomic::{AtomicUsize, Ordering},
+		time::Duration,
+	},
+	tokio::sync::Mutex,
+};
+
+/// Answers requests with scripted replies, in order, and keeps the requests
+/// for assertions. Share it with the client through an `Arc` to read them.
+///
+/// A request past the end of the script fails with
+/// [`AnthropicClientError::Transport`].
+#[derive(Debug, Default)]
+pub struct MockTransport {
+	replies: Vec<Reply>,
+	next: AtomicUsize,
+	requests: Mutex<Vec<HttpRequest>>,
+}
+
+#[derive(Debug)]
+struct Reply {
+	status: u16,
+	retry_after: Option<Duration>,
+	chunks: Vec<Bytes>,
+}
+
+impl MockTransport {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Reply with a raw body.
+	pub fn reply<B>(self, status: u16, body: B) -> Self
+	where
+		B: Into<Bytes>,
+	{
+		self.chunks(status, [body])
+	}
+
+	/// Reply with a raw body received in `chunks`, e.g. to split a line.
+	pub fn chunks<C, B>(mut self, status: u16, chunks: C) -> Self
+	where
+		C: IntoIterator<Item = B>,
+		B: Into<Bytes>,
+	{
+		self.replies.push(Reply { status, retry_after: None, chunks: chunks.into_iter().map(Into::into).collect() });
+		self
+	}
+
+	/// Reply with `body` as JSON, e.g. a [`crate::usage::TokenCount`] or a
+	/// [`crate::batch::MessageBatch`].
+	///
+	/// # Panics
+	///
+	/// When `body` does not serialize to JSON.
+	pub fn json<T>(self, body: &T) -> Self
+	where
+		T: Serialize + ?Sized,
+	{
+		self.reply(200, serde_json::to_vec(body).expect("mock replies serialize to JSON"))
+	}
+
+	/// Reply to a non-streaming message request.
+	pub fn message(self, message: &ResponseMessage) -> Self {
+		self.json(message)
+	}
+
+	/// Reply with server-sent events, one chunk per event.
+	///
+	/// # Panics
+	///
+	/// When an event does not serialize to JSON.
+	pub fn stream<E>(mut self, events: E) -> Self
+	where
+		E: IntoIterator<Item = Event>,
+	{
+		let chunks = events
+			.into_iter()
+			.map(|event| {
+				let data = serde_json::to_value(&event).expect("events serialize to JSON");
+				let name = data.get("type").and_then(|name| name.as_str()).unwrap_or("message").to_owned();
+				Bytes::from(format!("event: {name}\ndata: {data}\n\n"))
+			})
+			.collect();
+		self.replies.push(Reply { status: 200, retry_after: None, chunks });
+		self
+	}
+
+	/// Reply to a streaming request with the events the API would send for
+	/// `message`: every block starts empty and is filled by a single delta.
+	pub fn stream_message(self, message: &ResponseMessage) -> Self {
+		self.stream(events(message))
+	}
+
+	/// Reply with an API error, with its status.
+	pub fn error(self, error: AnthropicError) -> Self {
+		self.error_with_retry(error, None)
+	}
+
+	/// Reply with an API error and a `retry-after` header, for rate limits and
+	/// overloads.
+	///
+	/// # Panics
+	///
+	/// When `error` does not serialize to JSON.
+	pub fn error_with_retry(mut self, error: AnthropicError, retry_after: Option<Duration>) -> Self {
+		let body = serde_json::to_vec(&serde_json::json!({ "type": "error", "error": error })).expect("errors serialize to JSON");
+		self.replies.push(Reply { status: error.status().get(), retry_after, chunks: vec![body.into()] });
+		self
+	}
+
+	/// The requests received so far.
+	pub async fn requests(&self) -> Vec<HttpRequest> {
+		self.requests.lock().await.clone()
+	}
+
+	/// Scripted replies not sent yet.
+	pub fn remaining(&self) -> usize {
+		self.replies.len().saturating_sub(self.next.load(Ordering::SeqCst))
+	}
+}
+
+impl Transport for MockTransport {
+	fn send(&self, request: HttpRequest) -> BoxFuture<'_, AnthropicResult<HttpResponse>> {
+		Box::pin(async move {
+			let index = self.next.fetch_add(1, Ordering::SeqCst);
+			let path = request.path.clone();
+			self.requests.lock().await.push(request);
+			let reply = self.replies.get(index).ok_or_else(|| AnthropicClientError::Transport {
+				message: format!("no scripted reply for request {index} to {path}, the script has {} replies", self.replies.len()),
+			})?;
+			Ok(HttpResponse::from_chunks(reply.status, reply.retry_after, reply.chunks.clone()))
+		})
+	}
+}
+
+fn events(message: &ResponseMessage) -> Vec<Event> {
+	let mut start = message.clone();
+	start.message.content = Content::MultiPart(Vec::new());
+	start.stop_reason = None;
+	start.stop_sequence = None;
+	start.usage = Usage { output_tokens: 0, ..message.usage.clone() };
+
+	let blocks = match &message.message.content {
+		Content::SinglePart(text) => vec![Block::Text { text: text.clone(), cache_control: None }],
+		Content::MultiPart(blocks) => blocks.clone(),
+	};
+	let mut events = vec![Event::MessageStart { message: start }];
+	for (index, block) in blocks.into_iter().enumerate() {
+		let (content_block, deltas) = match block {
+			Block::Text { text, cache_control } => (Block::Text { text: String::new(), cache_control }, vec![Delta::Text { text }]),
+			Block::Thinking { thinking, signature } =>
+				(Block::Thinking { thinking: String::new(), signature: String::new() }, vec![Delta::Thinking { thinking }, Delta::Signature { signature }]),
+			Block::ToolUse { call: Use { id, name, input }, cache_control } =>
+				(Block::ToolUse { call: Use { id, name, input: serde_json::json!({}) }, cache_control }, vec![Delta::Json { partial_json: input.to_string() }]),
+			block => (block, Vec::new()),
+		};
+		events.push(Event::ContentBlockStart { index, content_block });
+		events.extend(deltas.into_iter().map(|delta| Event::ContentBlockDelta { index, delta }));
+		events.push(Event::ContentBlockStop { index });
+	}
+	events.push(Event::MessageStats {
+		delta: MessageStats { stop_reason: message.stop_reason.clone(), stop_sequence: message.stop_sequence.clone(), usage: Some(message.usage.clone()) },
+	});
+	events.push(Event::MessageStop);
+	events
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		crate::{
+			AnthropicClient, Model, Prompt,
+			chat_message::{ChatMessage, Role, StopReason},
+			client::RetryPolicy,
+			stream::FilterExt,
+		},
+		futures::StreamExt,
+		std::sync::Arc,
+	};
+
+	fn message() -> ResponseMessage {
+		ResponseMessage {
+			id: "msg_1".to_owned(),
+			message: ChatMessage {
+				role: Role::Assistant,
+				content: Content::MultiPart(vec![
+					Block::Thinking { thinking: "The user greets.".to_owned(), signature: "signature".to_owned() },
+					Block::text("Hello!"),
+					Block::ToolUse {
+						call: Use { id: "toolu_1".to_owned(), name: "weather".to_owned(), input: serde_json::json!({ "city": "Paris" }) },
+						cache_control: None,
+					},
+				]),
+			},
+			model: Model::default(),
+			stop_reason: Some(StopReason::ToolUse),
+			stop_sequence: None,
+			usage: Usage { input_tokens: 10, output_tokens: 20, ..Usage::default() },
+		}
+	}
+
+	fn prompt() -> Prompt {
+		Prompt::builder().messages(vec![ChatMessage::user("Hi")]).build(false)
+	}
+
+	fn rate_limit() -> AnthropicError {
+		AnthropicError::RateLimit { message: "slow down".to_owned() }
+	}
+
+	#[tokio::test]
+	async fn chat_returns_the_scripted_message() {
+		let mock = Arc::new(MockTransport::new().message(&message()));
+		let client = AnthropicClient::with_transport(mock.clone());
+		assert_eq!(client.chat(prompt()).await.unwrap(), message());
+
+		let requests = mock.requests().await;
+		assert_eq!(requests.len(), 1);
+		assert_eq!(requests[0].path, AnthropicClient::MESSAGES_PATH);
+		assert_eq!(requests[0].body.as_ref().and_then(|body| body.get("stream")), Some(&serde_json::json!(false)));
+		assert_eq!(mock.remaining(), 0);
+	}
+
+	#[tokio::test]
+	async fn streams_rebuild_the_scripted_message() {
+		let client = AnthropicClient::with_transport(Arc::new(MockTransport::new().stream_message(&message()).stream_message(&message())));
+		assert_eq!(client.stream_chat(prompt()).await.unwrap().final_message().await.unwrap(), message());
+
+		let text = client.stream_chat(prompt()).await.unwrap().text().collect::<Vec<_>>().await;
+		assert_eq!(text.into_iter().collect::<Result<Vec<_>, _>>().unwrap(), ["Hello!"]);
+	}
+
+	#[tokio::test]
+	async fn rate_limits_are_retried() {
+		let retry = RetryPolicy::builder().max_retries(2).initial_backoff(Duration::from_millis(1)).build();
+		let mock = Arc::new(MockTransport::new().error_with_retry(rate_limit(), Some(Duration::from_millis(1))).error(rate_limit()).message(&message()));
+		let client = AnthropicClient::builder().transport(mock.clone()).retry(retry).build().unwrap();
+		assert_eq!(client.chat(prompt()).await.unwrap(), message());
+		assert_eq!(mock.requests().await.len(), 3);
+	}
+
+	#[tokio::test]
+	async fn rate_limits_fail_past_the_retries() {
+		let retry = RetryPolicy::builder().max_retries(1).initial_backoff(Duration::from_millis(1)).build();
+		let mock = Arc::new(MockTransport::new().error(rate_limit()).error(rate_limit()).message(&message()));
+		let client = AnthropicClient::builder().transport(mock.clone()).retry(retry).build().unwrap();
+		assert!(matches!(client.chat(prompt()).await, Err(AnthropicClientError::Anthropic(AnthropicError::RateLimit { .. }))));
+		assert_eq!(mock.remaining(), 1);
+	}
+
+	#[tokio::test]
+	async fn other_errors_are_not_retried() {
+		let mock = Arc::new(MockTransport::new().error(AnthropicError::InvalidRequest { message: "bad".to_owned() }).message(&message()));
+		let client = AnthropicClient::with_transport(mock.clone());
+		assert!(matches!(client.chat(prompt()).await, Err(AnthropicClientError::Anthropic(AnthropicError::InvalidRequest { .. }))));
+		assert_eq!(mock.remaining(), 1);
+	}
+
+	#[tokio::test]
+	async fn requests_past_the_script_fail() {
+		let client = AnthropicClient::with_transport(Arc::new(MockTransport::new()));
+		assert!(matches!(client.chat(prompt()).await, Err(AnthropicClientError::Transport { .. })));
+	}
+}
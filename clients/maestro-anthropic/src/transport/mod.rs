@@ -0,0 +1,160 @@
+//! How an [`crate::AnthropicClient`] reaches the API.
+//!
+//! [`ReqwestTransport`] sends requests over HTTP and is the default.
+//! [`RecordTransport`] and [`ReplayTransport`] save exchanges to a fixture
+//! file and serve them back, [`MockTransport`] answers with scripted replies.
+//! Both let agents be tested without the live API.
+
+mod http;
+mod mock;
+mod replay;
+
+use {
+	crate::client::AnthropicResult,
+	bytes::Bytes,
+	futures::{StreamExt, future::BoxFuture, stream::BoxStream},
+	serde::{Deserialize, Serialize, de::DeserializeOwned},
+	serde_json::Value,
+	std::time::Duration,
+};
+pub use {
+	http::ReqwestTransport,
+	mock::MockTransport,
+	replay::{Cassette, Exchange, RecordTransport, RecordedBody, RecordedResponse, ReplayTransport},
+};
+
+/// Sends the requests of an [`crate::AnthropicClient`]. Retries and error
+/// parsing are done by the client, a transport only returns the raw response.
+pub trait Transport: Send + Sync {
+	fn send(&self, request: HttpRequest) -> BoxFuture<'_, AnthropicResult<HttpResponse>>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum_macros::Display)]
+#[serde(rename_all = "UPPERCASE")]
+#[strum(serialize_all = "UPPERCASE")]
+pub enum HttpMethod {
+	Get,
+	Post,
+}
+
+/// A request to the API, relative to the base URL of the transport.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpRequest {
+	pub method: HttpMethod,
+	/// E.g. [`crate::AnthropicClient::MESSAGES_PATH`].
+	pub path: String,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub query: Vec<(String, String)>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub body: Option<Value>,
+	/// Bounds the whole request. Not recorded.
+	#[serde(skip)]
+	pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+	/// A request without body.
+	pub fn new<P>(method: HttpMethod, path: P) -> Self
+	where
+		P: Into<String>,
+	{
+		Self { method, path: path.into(), query: Vec::new(), body: None, timeout: None }
+	}
+
+	pub fn get<P>(path: P) -> Self
+	where
+		P: Into<String>,
+	{
+		Self::new(HttpMethod::Get, path)
+	}
+
+	/// # Errors
+	///
+	/// Fails when `body` does not serialize to JSON.
+	pub fn post<P, B>(path: P, body: &B) -> AnthropicResult<Self>
+	where
+		P: Into<String>,
+		B: Serialize + ?Sized,
+	{
+		Ok(Self { body: Some(serde_json::to_value(body)?), ..Self::new(HttpMethod::Post, path) })
+	}
+
+	/// Add the fields of `query` to the query string, skipping `null`s.
+	///
+	/// # Errors
+	///
+	/// Fails when `query` does not serialize to JSON.
+	pub fn query<Q>(mut self, query: &Q) -> AnthropicResult<Self>
+	where
+		Q: Serialize + ?Sized,
+	{
+		if let Value::Object(fields) = serde_json::to_value(query)? {
+			self.query.extend(fields.into_iter().filter(|(_, value)| !value.is_null()).map(|(key, value)| match value {
+				Value::String(value) => (key, value),
+				value => (key, value.to_string()),
+			}));
+		}
+		Ok(self)
+	}
+
+	pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+		self.timeout = timeout;
+		self
+	}
+}
+
+/// A response body, streamed as it arrives.
+pub type Body = BoxStream<'static, Result<Bytes, reqwest::Error>>;
+
+pub struct HttpResponse {
+	pub status: u16,
+	/// The `retry-after` header.
+	pub retry_after: Option<Duration>,
+	pub body: Body,
+}
+
+impl HttpResponse {
+	/// A response with a body already in memory, sent as `chunks`.
+	pub fn from_chunks<C>(status: u16, retry_after: Option<Duration>, chunks: C) -> Self
+	where
+		C: IntoIterator<Item = Bytes>,
+		C::IntoIter: Send + 'static,
+	{
+		Self { status, retry_after, body: futures::stream::iter(chunks.into_iter().map(Ok)).boxed() }
+	}
+
+	pub fn is_success(&self) -> bool {
+		(200..300).contains(&self.status)
+	}
+
+	/// Read the whole body.
+	///
+	/// # Errors
+	///
+	/// Fails when the connection fails midway.
+	pub async fn bytes(mut self) -> Result<Bytes, reqwest::Error> {
+		let mut body = Vec::new();
+		while let Some(chunk) = self.body.next().await {
+			body.extend_from_slice(&chunk?);
+		}
+		Ok(body.into())
+	}
+
+	/// Read the whole body as JSON.
+	///
+	/// # Errors
+	///
+	/// Fails when the connection fails midway or the body is not a `T`.
+	pub async fn json<T>(self) -> AnthropicResult<T>
+	where
+		T: DeserializeOwned,
+	{
+		Ok(serde_json::from_slice(&self.bytes().await?)?)
+	}
+}
+
+impl std::fmt::Debug for HttpResponse {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("HttpResponse").field("status", &self.status).field("retry_after", &self.retry_after).finish_non_exhaustive()
+	}
+}
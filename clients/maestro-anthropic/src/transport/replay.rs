@@ -0,0 +1,241 @@
+use {
+	super::{HttpRequest, HttpResponse, Transport},
+	crate::client::{AnthropicClientError, AnthropicResult},
+	bytes::Bytes,
+	futures::future::BoxFuture,
+	serde::{Deserialize, Serialize},
+	serde_json::Value,
+	std::{
+		path::{Path, PathBuf},
+		sync::{
+			Arc,
+			atomic::{AtomicUsize, Ordering},
+		},
+		time::Duration,
+	},
+	tokio::sync::Mutex,
+};
+
+/// Recorded exchanges, in the order they were sent. Saved as pretty printed
+/// JSON so fixtures can be reviewed and edited by hand.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+	pub exchanges: Vec<Exchange>,
+}
+
+impl Cassette {
+	/// # Errors
+	///
+	/// Fails when the file cannot be read or is not a cassette.
+	pub fn load<P>(path: P) -> AnthropicResult<Self>
+	where
+		P: AsRef<Path>,
+	{
+		let path = path.as_ref();
+		let json = std::fs::read(path).map_err(|error| AnthropicClientError::Transport { message: format!("cannot read `{}`: {error}", path.display()) })?;
+		Ok(serde_json::from_slice(&json)?)
+	}
+
+	/// # Errors
+	///
+	/// Fails when the file cannot be written.
+	pub fn save<P>(&self, path: P) -> AnthropicResult<()>
+	where
+		P: AsRef<Path>,
+	{
+		let path = path.as_ref();
+		std::fs::write(path, serde_json::to_vec_pretty(self)?)
+			.map_err(|error| AnthropicClientError::Transport { message: format!("cannot write `{}`: {error}", path.display()) })
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Exchange {
+	pub request: HttpRequest,
+	pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+	pub status: u16,
+	/// The `retry-after` header, in seconds.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub retry_after: Option<f64>,
+	pub body: RecordedBody,
+}
+
+/// JSON bodies are kept as JSON, anything else, like server-sent events, as
+/// text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RecordedBody {
+	Text(String),
+	Json(Value),
+}
+
+impl RecordedBody {
+	fn new(body: &[u8]) -> Self {
+		match serde_json::from_slice(body) {
+			Ok(json) => Self::Json(json),
+			Err(_) => Self::Text(String::from_utf8_lossy(body).into_owned()),
+		}
+	}
+
+	/// Server-sent events are split after each event, so a replayed stream
+	/// arrives in several chunks like the recorded one.
+	fn chunks(&self) -> Vec<Bytes> {
+		match self {
+			Self::Text(text) => text.split_inclusive("\n\n").map(|event| Bytes::copy_from_slice(event.as_bytes())).collect(),
+			Self::Json(json) => vec![json.to_string().into()],
+		}
+	}
+}
+
+impl RecordedResponse {
+	fn replay(&self) -> HttpResponse {
+		let retry_after = self.retry_after.and_then(|seconds| Duration::try_from_secs_f64(seconds).ok());
+		HttpResponse::from_chunks(self.status, retry_after, self.body.chunks())
+	}
+}
+
+/// Forwards requests to another transport and saves every exchange to a
+/// [`Cassette`] file, rewritten after each response. Streams are read to the
+/// end before being returned.
+pub struct RecordTransport {
+	inner: Arc<dyn Transport>,
+	path: PathBuf,
+	cassette: Mutex<Cassette>,
+}
+
+impl RecordTransport {
+	/// Record to `path`, replacing its content.
+	pub fn new<P>(inner: Arc<dyn Transport>, path: P) -> Self
+	where
+		P: Into<PathBuf>,
+	{
+		Self { inner, path: path.into(), cassette: Mutex::default() }
+	}
+}
+
+impl Transport for RecordTransport {
+	fn send(&self, request: HttpRequest) -> BoxFuture<'_, AnthropicResult<HttpResponse>> {
+		Box::pin(async move {
+			let response = self.inner.send(request.clone()).await?;
+			let (status, retry_after) = (response.status, response.retry_after);
+			let body = response.bytes().await?;
+			let recorded = RecordedResponse { status, retry_after: retry_after.map(|delay| delay.as_secs_f64()), body: RecordedBody::new(&body) };
+			let mut cassette = self.cassette.lock().await;
+			cassette.exchanges.push(Exchange { request, response: recorded });
+			cassette.save(&self.path)?;
+			Ok(HttpResponse::from_chunks(status, retry_after, [body]))
+		})
+	}
+}
+
+/// Serves the exchanges of a [`Cassette`] in order. Each request must match
+/// the recorded one, timeouts aside.
+#[derive(Debug)]
+pub struct ReplayTransport {
+	cassette: Cassette,
+	next: AtomicUsize,
+}
+
+impl ReplayTransport {
+	pub fn new(cassette: Cassette) -> Self {
+		Self { cassette, next: AtomicUsize::new(0) }
+	}
+
+	/// # Errors
+	///
+	/// Fails when the file cannot be read or is not a cassette.
+	pub fn open<P>(path: P) -> AnthropicResult<Self>
+	where
+		P: AsRef<Path>,
+	{
+		Ok(Self::new(Cassette::load(path)?))
+	}
+
+	/// Exchanges not requested yet.
+	pub fn remaining(&self) -> usize {
+		self.cassette.exchanges.len().saturating_sub(self.next.load(Ordering::SeqCst))
+	}
+}
+
+impl Transport for ReplayTransport {
+	fn send(&self, request: HttpRequest) -> BoxFuture<'_, AnthropicResult<HttpResponse>> {
+		let index = self.next.fetch_add(1, Ordering::SeqCst);
+		let response = match self.cassette.exchanges.get(index) {
+			None => Err(AnthropicClientError::Transport { message: format!("no recorded exchange for request {index} ({} {})", request.method, request.path) }),
+			Some(exchange) if exchange.request.method != request.method || exchange.request.path != request.path => Err(AnthropicClientError::Transport {
+				message: format!("request {index} is {} {}, the recorded one is {} {}", request.method, request.path, exchange.request.method, exchange.request.path),
+			}),
+			Some(exchange) if exchange.request.query != request.query || exchange.request.body != request.body => Err(AnthropicClientError::Transport {
+				message: format!("request {index} to {} does not match the recorded one, record the fixture again", request.path),
+			}),
+			Some(exchange) => Ok(exchange.response.replay()),
+		};
+		Box::pin(std::future::ready(response))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		crate::{
+			AnthropicClient, Model, Prompt, ResponseMessage,
+			chat_message::{Block, ChatMessage, Content, Role, StopReason, Usage},
+			stream::FilterExt,
+			transport::MockTransport,
+		},
+	};
+
+	fn message() -> ResponseMessage {
+		ResponseMessage {
+			id: "msg_1".to_owned(),
+			message: ChatMessage { role: Role::Assistant, content: Content::MultiPart(vec![Block::text("Hello!")]) },
+			model: Model::default(),
+			stop_reason: Some(StopReason::EndTurn),
+			stop_sequence: None,
+			usage: Usage { input_tokens: 10, output_tokens: 3, ..Usage::default() },
+		}
+	}
+
+	fn prompt(text: &str) -> Prompt {
+		Prompt::builder().messages(vec![ChatMessage::user(text)]).build(false)
+	}
+
+	#[tokio::test]
+	async fn recorded_exchanges_replay() {
+		let dir = tempdir::TempDir::new("maestro-anthropic").unwrap();
+		let path = dir.path().join("cassette.json");
+		let mock: Arc<dyn Transport> = Arc::new(MockTransport::new().message(&message()).stream_message(&message()));
+		let client = AnthropicClient::with_transport(Arc::new(RecordTransport::new(mock, &path)));
+		assert_eq!(client.chat(prompt("Hi")).await.unwrap(), message());
+		assert_eq!(client.stream_chat(prompt("Hi")).await.unwrap().final_message().await.unwrap(), message());
+
+		let cassette = Cassette::load(&path).unwrap();
+		assert_eq!(cassette.exchanges.len(), 2);
+		assert!(matches!(cassette.exchanges[0].response.body, RecordedBody::Json(_)));
+		assert!(matches!(cassette.exchanges[1].response.body, RecordedBody::Text(_)));
+
+		let replay = Arc::new(ReplayTransport::new(cassette.clone()));
+		let client = AnthropicClient::with_transport(replay.clone());
+		assert_eq!(client.chat(prompt("Hi")).await.unwrap(), message());
+		assert_eq!(client.stream_chat(prompt("Hi")).await.unwrap().final_message().await.unwrap(), message());
+		assert_eq!(replay.remaining(), 0);
+		assert!(matches!(client.chat(prompt("Hi")).await, Err(AnthropicClientError::Transport { .. })));
+	}
+
+	#[tokio::test]
+	async fn replays_refuse_other_requests() {
+		let cassette = Cassette {
+			exchanges: vec![Exchange {
+				request: HttpRequest::post(AnthropicClient::MESSAGES_PATH, &prompt("Hi")).unwrap(),
+				response: RecordedResponse { status: 200, retry_after: None, body: RecordedBody::Json(serde_json::to_value(message()).unwrap()) },
+			}],
+		};
+		let client = AnthropicClient::with_transport(Arc::new(ReplayTransport::new(cassette)));
+		assert!(matches!(client.chat(prompt("Bye")).await, Err(AnthropicClientError::Transport { .. })));
+	}
+}
@@ -0,0 +1,104 @@
+use {
+	crate::{
+		Model,
+		model::Pricing,
+		response::{ResponseMessage, Usage},
+		stream::Event,
+	},
+	serde::{Deserialize, Serialize},
+	std::collections::BTreeMap,
+};
+
+impl Pricing {
+	/// Cost of `usage` in USD.
+	pub fn cost(&self, usage: &Usage) -> f64 {
+		const MILLION: f64 = 1_000_000.0;
+		#[allow(clippy::cast_precision_loss)]
+		let tokens = |count: u64| count as f64 / MILLION;
+		tokens(usage.input_tokens) * self.input
+			+ tokens(usage.output_tokens) * self.output
+			+ tokens(usage.cache_creation_input_tokens) * self.cache_write
+			+ tokens(usage.cache_read_input_tokens) * self.cache_read
+	}
+}
+
+impl Usage {
+	/// Cost of this usage in USD when billed for `model`, `None` when the
+	/// model has no known pricing.
+	pub fn cost(&self, model: &Model) -> Option<f64> {
+		model.pricing().map(|pricing| pricing.cost(self))
+	}
+}
+
+/// Sums [`Usage`] per [`Model`] across calls and streams. It serializes so it
+/// can be stored alongside a user to enforce budgets.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageTracker {
+	by_model: BTreeMap<Model, Usage>,
+	#[serde(skip)]
+	streaming: Option<(Model, Usage)>,
+}
+
+impl UsageTracker {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn record(&mut self, model: Model, usage: &Usage) {
+		*self.by_model.entry(model).or_default() += usage;
+	}
+
+	pub fn record_message(&mut self, message: &ResponseMessage) {
+		self.record(message.model.clone(), &message.usage);
+	}
+
+	/// Track the usage of a stream event by event. The usage of
+	/// [`Event::MessageStart`] is updated by every [`Event::MessageStats`] and
+	/// recorded on [`Event::MessageStop`], so events of concurrent streams must
+	/// go to different trackers.
+	pub fn observe(&mut self, event: &Event) {
+		match event {
+			Event::MessageStart { message } => self.streaming = Some((message.model.clone(), message.usage.clone())),
+			Event::MessageStats { delta } =>
+				if let (Some((_, usage)), Some(delta)) = (&mut self.streaming, &delta.usage) {
+					usage.apply_delta(delta);
+				},
+			Event::MessageStop =>
+				if let Some((model, usage)) = self.streaming.take() {
+					self.record(model, &usage);
+				},
+			_ => {},
+		}
+	}
+
+	pub fn by_model(&self) -> &BTreeMap<Model, Usage> {
+		&self.by_model
+	}
+
+	pub fn total(&self) -> Usage {
+		self.by_model.values().fold(Usage::default(), |mut total, usage| {
+			total += usage;
+			total
+		})
+	}
+
+	/// Total cost in USD. Models without known pricing, see
+	/// [`Model::Custom`], are not counted.
+	pub fn cost(&self) -> f64 {
+		self.by_model.iter().filter_map(|(model, usage)| usage.cost(model)).sum()
+	}
+
+	pub fn exceeds(&self, budget: f64) -> bool {
+		self.cost() > budget
+	}
+
+	pub fn reset(&mut self) {
+		*self = Self::default();
+	}
+}
+
+/// Response of [`crate::AnthropicClient::count_tokens`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenCount {
+	pub input_tokens: u64,
+}